@@ -80,6 +80,7 @@ impl ContributorRegistryContract {
             .get(&DataKey::Contributor(contributor_address.clone()))
             .ok_or(ContributorError::ContributorNotFound)?;
 
+        #[allow(clippy::manual_saturating_arithmetic)]
         let new_score = if delta > 0 {
             contributor
                 .reputation_score
@@ -128,6 +129,11 @@ impl ContributorRegistryContract {
             .ok_or(ContributorError::NotInitialized)
     }
 
+    /// Return this contract's version identifier.
+    pub fn version() -> u32 {
+        1
+    }
+
     /// Upgrade the contract WASM to a new hash.
     ///
     /// Only the stored admin may call this. Emits [`UpgradedEvent`] on success.
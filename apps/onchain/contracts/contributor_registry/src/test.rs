@@ -337,6 +337,14 @@ fn test_reputation_get_reputation() {
     assert_eq!(client.get_reputation(&contributor), 50);
 }
 
+#[test]
+fn test_version() {
+    let env = Env::default();
+
+    let (client, _, _) = setup_test(&env);
+    assert_eq!(client.version(), 1);
+}
+
 // ---------------------------------------------------------------------------
 // Upgradeability tests
 // ---------------------------------------------------------------------------
@@ -15,4 +15,38 @@ pub enum CrowdfundError {
     AlreadyRegistered = 9,
     ContributorNotFound = 10,
     ContractPaused = 11,
+    CommitmentNotFound = 12,
+    CommitmentAlreadyExists = 13,
+    TokenMismatch = 14,
+    InvalidFee = 15,
+    LengthMismatch = 16,
+    ReputationTooLow = 17,
+    MilestoneNotSubmitted = 18,
+    MilestoneAlreadySubmitted = 19,
+    InvalidPenalty = 20,
+    ProjectStillActive = 21,
+    BalanceAboveDustThreshold = 22,
+    TooManyMilestones = 23,
+    InvalidExponent = 24,
+    NoPendingAdmin = 25,
+    Overflow = 26,
+    DepositCapExceeded = 27,
+    DeadlineAlreadySet = 28,
+    InvalidDeadline = 29,
+    InvalidToken = 30,
+    WithdrawalTooEarly = 31,
+    ProjectHasDeposits = 32,
+    RoundNotFound = 33,
+    PendingDepositNotFound = 34,
+    DepositCooldownActive = 35,
+    MatchingPoolTooLow = 36,
+    TokenTransferFailed = 37,
+    ProjectCreationDisabled = 38,
+    NotRefundable = 39,
+    NotAllowlisted = 40,
+    InvalidPrecision = 41,
+    InvalidName = 42,
+    DecimalsMismatch = 43,
+    IdempotencyKeyMismatch = 44,
+    DeadlinePassed = 45,
 }
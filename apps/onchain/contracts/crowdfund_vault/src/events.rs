@@ -1,9 +1,13 @@
-use soroban_sdk::{contractevent, Address};
+//! Contract events. Every event carries `seq`, a contract-wide counter that
+//! increases by one per emitted event so consumers can order events and detect gaps.
+
+use soroban_sdk::{contractevent, Address, BytesN};
 
 #[contractevent]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct InitializedEvent {
     pub admin: Address,
+    pub seq: u64,
 }
 
 #[contractevent]
@@ -14,6 +18,7 @@ pub struct ProjectCreatedEvent {
     #[topic]
     pub token_address: Address,
     pub project_id: u64,
+    pub seq: u64,
 }
 
 #[contractevent]
@@ -24,6 +29,97 @@ pub struct DepositEvent {
     #[topic]
     pub project_id: u64,
     pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContributionIncreasedEvent {
+    #[topic]
+    pub project_id: u64,
+    #[topic]
+    pub contributor: Address,
+    pub added: i128,
+    pub new_total: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenDepositEvent {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub project_id: u64,
+    pub token_address: Address,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenWithdrawEvent {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub project_id: u64,
+    pub token_address: Address,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositCommittedEvent {
+    #[topic]
+    pub user: Address,
+    pub commitment: BytesN<32>,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositRevealedEvent {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub project_id: u64,
+    pub commitment: BytesN<32>,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RefundClaimedEvent {
+    #[topic]
+    pub contributor: Address,
+    #[topic]
+    pub project_id: u64,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TargetReachedEvent {
+    #[topic]
+    pub project_id: u64,
+    pub total_deposited: i128,
+    pub timestamp: u64,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneSubmittedEvent {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub project_id: u64,
+    pub index: u32,
+    pub seq: u64,
 }
 
 #[contractevent]
@@ -32,6 +128,18 @@ pub struct MilestoneApprovedEvent {
     #[topic]
     pub admin: Address,
     pub project_id: u64,
+    pub index: u32,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneRejectedEvent {
+    #[topic]
+    pub admin: Address,
+    pub project_id: u64,
+    pub index: u32,
+    pub seq: u64,
 }
 
 #[contractevent]
@@ -42,12 +150,296 @@ pub struct WithdrawEvent {
     #[topic]
     pub project_id: u64,
     pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchFeeUpdatedEvent {
+    #[topic]
+    pub admin: Address,
+    pub fee_bps: u32,
+    pub collector: Address,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositFeeUpdatedEvent {
+    #[topic]
+    pub admin: Address,
+    pub fee_bps: u32,
+    pub collector: Address,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegistrationFeeUpdatedEvent {
+    #[topic]
+    pub admin: Address,
+    pub fee: i128,
+    pub fee_token: Address,
+    pub collector: Address,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SybilPenaltyUpdatedEvent {
+    #[topic]
+    pub admin: Address,
+    pub penalty_bps: u32,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MaxMatchRatioUpdatedEvent {
+    #[topic]
+    pub admin: Address,
+    pub ratio_bps: u32,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinDurationUpdatedEvent {
+    #[topic]
+    pub admin: Address,
+    pub min_duration: u64,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchingPoolFundedEvent {
+    #[topic]
+    pub donor: Address,
+    #[topic]
+    pub token_address: Address,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchDistributedEvent {
+    #[topic]
+    pub project_id: u64,
+    #[topic]
+    pub token_address: Address,
+    pub round: u64,
+    pub amount: i128,
+    pub fee: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinPoolBalanceUpdatedEvent {
+    #[topic]
+    pub admin: Address,
+    pub min_pool_balance: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreationEnabledUpdatedEvent {
+    #[topic]
+    pub admin: Address,
+    pub enabled: bool,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchingTokenUpdatedEvent {
+    #[topic]
+    pub project_id: u64,
+    pub matching_token: Address,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchReservedEvent {
+    #[topic]
+    pub project_id: u64,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchRoundStartedEvent {
+    #[topic]
+    pub admin: Address,
+    pub round: u64,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchExponentUpdatedEvent {
+    #[topic]
+    pub admin: Address,
+    pub exponent: u32,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SqrtPrecisionUpdatedEvent {
+    #[topic]
+    pub admin: Address,
+    pub iterations: u32,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchThresholdUpdatedEvent {
+    #[topic]
+    pub admin: Address,
+    pub min_contributors: u32,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositThresholdUpdatedEvent {
+    #[topic]
+    pub admin: Address,
+    pub threshold: Option<i128>,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositHeldEvent {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub project_id: u64,
+    pub deposit_id: u64,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositRejectedEvent {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub project_id: u64,
+    pub deposit_id: u64,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositCapUpdatedEvent {
+    #[topic]
+    pub project_id: u64,
+    pub max_total_deposit: Option<i128>,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositCooldownUpdatedEvent {
+    #[topic]
+    pub project_id: u64,
+    pub cooldown: u64,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowlistUpdatedEvent {
+    #[topic]
+    pub project_id: u64,
+    pub enabled: bool,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContributorAllowedEvent {
+    #[topic]
+    pub project_id: u64,
+    #[topic]
+    pub contributor: Address,
+    pub allowed: bool,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectTokenChangedEvent {
+    #[topic]
+    pub project_id: u64,
+    pub old_token: Address,
+    pub new_token: Address,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeadlineSetEvent {
+    #[topic]
+    pub project_id: u64,
+    pub end_time: u64,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeadlineExtendedEvent {
+    #[topic]
+    pub project_id: u64,
+    pub old_end_time: u64,
+    pub new_end_time: u64,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectClosedEvent {
+    #[topic]
+    pub admin: Address,
+    pub project_id: u64,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DustSweptEvent {
+    #[topic]
+    pub project_id: u64,
+    pub to: Address,
+    pub amount: i128,
+    pub seq: u64,
 }
 
 #[contractevent]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ContributorRegisteredEvent {
     pub contributor: Address,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContributorsDedupedEvent {
+    #[topic]
+    pub project_id: u64,
+    pub removed: u32,
+    pub seq: u64,
 }
 
 #[contractevent]
@@ -57,6 +449,16 @@ pub struct ReputationUpdatedEvent {
     pub contributor: Address,
     pub old_reputation: i128,
     pub new_reputation: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventModeUpdatedEvent {
+    #[topic]
+    pub admin: Address,
+    pub minimal: bool,
+    pub seq: u64,
 }
 
 #[contractevent]
@@ -66,6 +468,7 @@ pub struct ContractPauseEvent {
     pub admin: Address,
     pub paused: bool,
     pub timestamp: u64,
+    pub seq: u64,
 }
 
 #[contractevent]
@@ -75,6 +478,7 @@ pub struct ContractUnpauseEvent {
     pub admin: Address,
     pub paused: bool,
     pub timestamp: u64,
+    pub seq: u64,
 }
 
 /// Emitted when the contract WASM is upgraded to a new hash.
@@ -83,6 +487,16 @@ pub struct UpgradedEvent {
     #[topic]
     pub admin: Address,
     pub new_wasm_hash: soroban_sdk::BytesN<32>,
+    pub seq: u64,
+}
+
+/// Emitted when a new admin is proposed and awaits acceptance.
+#[contractevent]
+pub struct AdminProposedEvent {
+    #[topic]
+    pub admin: Address,
+    pub pending_admin: Address,
+    pub seq: u64,
 }
 
 /// Emitted when the admin role is transferred to a new address.
@@ -91,4 +505,5 @@ pub struct AdminChangedEvent {
     #[topic]
     pub old_admin: Address,
     pub new_admin: Address,
+    pub seq: u64,
 }
@@ -7,9 +7,45 @@ mod storage;
 mod token;
 
 use errors::CrowdfundError;
-use math::{sqrt_scaled, unscale};
-use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Symbol};
-use storage::{DataKey, ProjectData};
+use math::{
+    denormalize_amount, mul_div_rem, normalize_amount, root_scaled, saturating_pow_scaled,
+    saturating_sum, unscale, DEFAULT_SQRT_ITERATIONS, MAX_SQRT_ITERATIONS, SCALE,
+};
+use soroban_sdk::{contract, contractimpl, Address, Bytes, BytesN, Env, Map, Symbol, Vec};
+use storage::{
+    DataKey, DepositCommitment, ExtDataKey, MilestoneStatus, PendingDeposit, ProjectData,
+    ProjectSummary, RoundData,
+};
+
+/// Basis points denominator for fees and penalties (100%)
+const MAX_FEE_BPS: u32 = 10_000;
+
+/// Maximum number of milestones a project may use (indices `0..MAX_MILESTONES`)
+const MAX_MILESTONES: u32 = 10;
+
+/// Default matching exponent k; 2 is standard quadratic funding
+const DEFAULT_MATCH_EXPONENT: u32 = 2;
+
+/// Largest matching exponent the admin may configure
+const MAX_MATCH_EXPONENT: u32 = 4;
+
+/// Largest residual balance `sweep_dust` will clear from a closed project
+const DUST_THRESHOLD: i128 = 1_000;
+
+/// Most projects `get_projects_summary` returns in one page
+const MAX_PAGE_SIZE: u32 = 50;
+
+/// Most entries kept in the reputation leaderboard `get_top_reputation` reads
+const MAX_LEADERBOARD_SIZE: u32 = 25;
+
+/// Most entries kept per contributor in `get_deposit_history`; older ones are dropped
+const MAX_DEPOSIT_HISTORY: u32 = 50;
+
+/// Most entries kept per project in `get_withdraw_history`; older ones are dropped
+const MAX_WITHDRAW_HISTORY: u32 = 50;
+
+/// Ledgers a deposit idempotency key is remembered for (about one day)
+const IDEMPOTENCY_KEY_TTL: u32 = 17_280;
 
 #[contract]
 pub struct CrowdfundVaultContract;
@@ -17,7 +53,14 @@ pub struct CrowdfundVaultContract;
 #[contractimpl]
 impl CrowdfundVaultContract {
     /// Initialize the contract with an admin address
-    pub fn initialize(env: Env, admin: Address) -> Result<(), CrowdfundError> {
+    ///
+    /// An optional `guardian` may unpause the contract if the admin key is lost or
+    /// compromised while paused; it has no other privileges.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        guardian: Option<Address>,
+    ) -> Result<(), CrowdfundError> {
         // Check if already initialized
         if env.storage().instance().has(&DataKey::Admin) {
             return Err(CrowdfundError::AlreadyInitialized);
@@ -29,6 +72,11 @@ impl CrowdfundVaultContract {
         // Store admin address
         env.storage().instance().set(&DataKey::Admin, &admin);
 
+        // Store recovery guardian
+        if let Some(guardian) = guardian {
+            env.storage().instance().set(&DataKey::Guardian, &guardian);
+        }
+
         // Store Emergency Pause bool
         env.storage().instance().set(&DataKey::Paused, &false);
 
@@ -36,18 +84,30 @@ impl CrowdfundVaultContract {
         env.storage().instance().set(&DataKey::NextProjectId, &0u64);
 
         // Emit initialization event
-        events::InitializedEvent { admin }.publish(&env);
+        if Self::full_events(&env) {
+            events::InitializedEvent {
+                admin,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
 
         Ok(())
     }
 
     /// Create a new project
+    ///
+    /// A `target_amount` of zero creates an open-ended project with no funding goal.
+    /// When `min_reputation` is set, only contributors with at least that
+    /// reputation may deposit; unregistered users count as zero reputation.
     pub fn create_project(
         env: Env,
         owner: Address,
         name: Symbol,
         target_amount: i128,
         token_address: Address,
+        min_reputation: Option<i128>,
+        category: Symbol,
     ) -> Result<u64, CrowdfundError> {
         // Check if contract is initialized
         if !env.storage().instance().has(&DataKey::Admin) {
@@ -62,11 +122,26 @@ impl CrowdfundVaultContract {
             return Err(CrowdfundError::ContractPaused);
         };
 
-        // Validate target amount
-        if target_amount <= 0 {
+        // Check the creation freeze
+        if !Self::is_creation_enabled(env.clone()) {
+            return Err(CrowdfundError::ProjectCreationDisabled);
+        }
+
+        // Reject empty names; a Symbol already bounds the length to 32 characters
+        if name == Symbol::new(&env, "") {
+            return Err(CrowdfundError::InvalidName);
+        }
+
+        // Validate target amount (zero means open-ended)
+        if target_amount < 0 {
             return Err(CrowdfundError::InvalidAmount);
         }
 
+        // Reject addresses that aren't token contracts before any deposit can fail on them
+        if !token::is_token(&env, &token_address) {
+            return Err(CrowdfundError::InvalidToken);
+        }
+
         // Get next project ID
         let project_id: u64 = env
             .storage()
@@ -84,6 +159,12 @@ impl CrowdfundVaultContract {
             total_deposited: 0,
             total_withdrawn: 0,
             is_active: true,
+            min_reputation,
+            max_total_deposit: None,
+            end_time: None,
+            created_at: env.ledger().timestamp(),
+            closed_at: None,
+            category: category.clone(),
         };
 
         // Store project
@@ -102,294 +183,3959 @@ impl CrowdfundVaultContract {
             .persistent()
             .set(&DataKey::MilestoneApproved(project_id), &false);
 
+        // Index the project under its category
+        let category_count_key = DataKey::CategoryProjectCount(category.clone());
+        let category_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&category_count_key)
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &ExtDataKey::CategoryProject(category.clone(), category_count),
+            &project_id,
+        );
+        env.storage()
+            .persistent()
+            .set(&category_count_key, &(category_count + 1));
+
         // Increment project ID counter
         env.storage()
             .instance()
             .set(&DataKey::NextProjectId, &(project_id + 1));
 
+        // Track how many projects are running
+        let active_count = Self::get_active_project_count(env.clone());
+        env.storage()
+            .instance()
+            .set(&ExtDataKey::ActiveProjectCount, &(active_count + 1));
+
         // Emit project creation event
-        events::ProjectCreatedEvent {
-            owner,
-            token_address,
-            project_id,
+        if Self::full_events(&env) {
+            events::ProjectCreatedEvent {
+                owner,
+                token_address,
+                project_id,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
         }
-        .publish(&env);
 
         Ok(project_id)
     }
 
     /// Deposit funds into a project
+    ///
+    /// A retried call carrying an `idempotency_key` the user already deposited
+    /// with succeeds without crediting again; reusing the key with a different
+    /// project or amount fails with `IdempotencyKeyMismatch`. A key is only
+    /// recorded once its deposit succeeds, and is remembered for
+    /// `IDEMPOTENCY_KEY_TTL` ledgers.
     pub fn deposit(
         env: Env,
         user: Address,
         project_id: u64,
         amount: i128,
+        idempotency_key: Option<BytesN<32>>,
     ) -> Result<(), CrowdfundError> {
-        // Check if contract is initialized
-        if !env.storage().instance().has(&DataKey::Admin) {
-            return Err(CrowdfundError::NotInitialized);
+        // A replay is answered before `check_deposit`, which would restart the cooldown
+        let idempotency_key =
+            idempotency_key.map(|key| ExtDataKey::DepositIdempotency(user.clone(), key));
+        if let Some(key) = &idempotency_key {
+            if let Some(args) = env.storage().temporary().get::<_, (u64, i128)>(key) {
+                user.require_auth();
+                if args != (project_id, amount) {
+                    return Err(CrowdfundError::IdempotencyKeyMismatch);
+                }
+                return Ok(());
+            }
         }
 
-        // Require user authorization
-        user.require_auth();
+        let mut project = Self::check_deposit(&env, &user, project_id, amount)?;
 
-        // Check Emergency Pause State
-        if Self::require_not_paused(&env) {
-            return Err(CrowdfundError::ContractPaused);
+        // Transfer tokens from user to contract if they have sufficient balance; otherwise, skip transfer for accounting-only updates
+        let user_balance = token::balance(&env, &project.token_address, &user);
+        let funded = user_balance >= amount;
+
+        // The fee is only taken from tokens actually received
+        let (fee, net_amount) = if funded {
+            Self::split_deposit_fee(&env, amount)?
+        } else {
+            (0, amount)
         };
+        Self::check_deposit_cap(&project, net_amount)?;
 
-        // Validate amount
-        if amount <= 0 {
-            return Err(CrowdfundError::InvalidAmount);
+        if funded {
+            token::transfer(
+                &env,
+                &project.token_address,
+                &user,
+                &env.current_contract_address(),
+                &amount,
+            )?;
         }
 
-        // Get project
-        let mut project: ProjectData = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Project(project_id))
-            .ok_or(CrowdfundError::ProjectNotFound)?;
+        Self::pay_deposit_fee(&env, &project.token_address, fee)?;
+        Self::credit_deposit(&env, &mut project, user, net_amount)?;
 
-        // Check if project is active
-        if !project.is_active {
-            return Err(CrowdfundError::ProjectNotActive);
+        if let Some(key) = idempotency_key {
+            env.storage().temporary().set(&key, &(project_id, amount));
+            env.storage()
+                .temporary()
+                .extend_ttl(&key, IDEMPOTENCY_KEY_TTL, IDEMPOTENCY_KEY_TTL);
         }
+        Ok(())
+    }
 
-        // Transfer tokens from user to contract if they have sufficient balance; otherwise, skip transfer for accounting-only updates
+    /// Deposit by having the contract pull `amount` using an allowance `user` granted it
+    ///
+    /// The user approves this contract on the project token beforehand; the
+    /// contract then debits them with `transfer_from` instead of a push transfer.
+    pub fn deposit_via_allowance(
+        env: Env,
+        user: Address,
+        project_id: u64,
+        amount: i128,
+    ) -> Result<(), CrowdfundError> {
+        let mut project = Self::check_deposit(&env, &user, project_id, amount)?;
+        let (fee, net_amount) = Self::split_deposit_fee(&env, amount)?;
+        Self::check_deposit_cap(&project, net_amount)?;
+
+        // Pull tokens from user to contract under the granted allowance
         let contract_address = env.current_contract_address();
-        let user_balance = token::balance(&env, &project.token_address, &user);
-        if user_balance >= amount {
-            token::transfer(
-                &env,
-                &project.token_address,
-                &user,
-                &contract_address,
-                &amount,
-            );
+        token::transfer_from(
+            &env,
+            &project.token_address,
+            &contract_address,
+            &user,
+            &contract_address,
+            &amount,
+        )?;
+
+        Self::pay_deposit_fee(&env, &project.token_address, fee)?;
+        Self::credit_deposit(&env, &mut project, user, net_amount)
+    }
+
+    /// Split a deposit of `amount` into the platform fee and the amount credited
+    fn split_deposit_fee(env: &Env, amount: i128) -> Result<(i128, i128), CrowdfundError> {
+        let fee_bps: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::DepositFeeBps)
+            .unwrap_or(0);
+        let fee = amount
+            .checked_mul(fee_bps as i128)
+            .ok_or(CrowdfundError::Overflow)?
+            / MAX_FEE_BPS as i128;
+
+        // Nothing would be left to credit
+        let net_amount = amount - fee;
+        if net_amount <= 0 {
+            return Err(CrowdfundError::InvalidAmount);
         }
 
-        // Update project balance
-        let balance_key = DataKey::ProjectBalance(project_id, project.token_address.clone());
-        let current_balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
-        env.storage()
-            .persistent()
-            .set(&balance_key, &(current_balance + amount));
+        Ok((fee, net_amount))
+    }
 
-        // Track individual contribution for quadratic funding
-        let contribution_key = DataKey::Contribution(project_id, user.clone());
+    /// Route a deposit fee already received by the contract to the fee collector
+    fn pay_deposit_fee(
+        env: &Env,
+        token_address: &Address,
+        fee: i128,
+    ) -> Result<(), CrowdfundError> {
+        if fee <= 0 {
+            return Ok(());
+        }
+        let collector: Address = env
+            .storage()
+            .instance()
+            .get(&ExtDataKey::DepositFeeCollector)
+            .ok_or(CrowdfundError::NotInitialized)?;
+        token::transfer(
+            env,
+            token_address,
+            &env.current_contract_address(),
+            &collector,
+            &fee,
+        )
+    }
+
+    /// Deposit a token other than the project's own token
+    ///
+    /// Secondary-token deposits are held and tracked per token, and can be
+    /// withdrawn with `withdraw_token`. They don't count toward the target, the
+    /// deposit cap or quadratic matching, which are all denominated in the
+    /// project token. Depositing the project token behaves like `deposit`.
+    pub fn deposit_token(
+        env: Env,
+        user: Address,
+        project_id: u64,
+        token_address: Address,
+        amount: i128,
+    ) -> Result<(), CrowdfundError> {
+        if token_address == Self::get_project_token(env.clone(), project_id)? {
+            return Self::deposit(env, user, project_id, amount, None);
+        }
+        Self::check_deposit(&env, &user, project_id, amount)?;
+
+        if !token::is_token(&env, &token_address) {
+            return Err(CrowdfundError::InvalidToken);
+        }
+
+        // Compute the new totals before moving any funds
+        let contribution_key =
+            DataKey::TokenContribution(project_id, user.clone(), token_address.clone());
         let current_contribution: i128 = env
             .storage()
             .persistent()
             .get(&contribution_key)
             .unwrap_or(0);
+        let new_contribution = current_contribution
+            .checked_add(amount)
+            .ok_or(CrowdfundError::Overflow)?;
+        let balance_key = DataKey::ProjectBalance(project_id, token_address.clone());
+        let current_balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+        let new_balance = current_balance
+            .checked_add(amount)
+            .ok_or(CrowdfundError::Overflow)?;
 
-        // If this is a new contributor, add them to the contributors list
-        if current_contribution == 0 {
-            let contributor_count_key = DataKey::ContributorCount(project_id);
-            let contributor_count: u32 = env
-                .storage()
-                .persistent()
-                .get(&contributor_count_key)
-                .unwrap_or(0);
+        // Transfer tokens from user to contract
+        token::transfer(
+            &env,
+            &token_address,
+            &user,
+            &env.current_contract_address(),
+            &amount,
+        )?;
 
-            // Store contributor at index
-            env.storage()
-                .persistent()
-                .set(&DataKey::Contributor(project_id, contributor_count), &user);
+        env.storage().persistent().set(&balance_key, &new_balance);
+        env.storage()
+            .persistent()
+            .set(&contribution_key, &new_contribution);
 
-            // Increment contributor count
+        // Remember which secondary tokens the project holds
+        let project_tokens_key = DataKey::ProjectTokens(project_id);
+        let mut project_tokens: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&project_tokens_key)
+            .unwrap_or(Vec::new(&env));
+        if !project_tokens.contains(&token_address) {
+            project_tokens.push_back(token_address.clone());
             env.storage()
                 .persistent()
-                .set(&contributor_count_key, &(contributor_count + 1));
+                .set(&project_tokens_key, &project_tokens);
         }
 
-        // Update contribution amount
-        env.storage()
-            .persistent()
-            .set(&contribution_key, &(current_contribution + amount));
+        // Remember which secondary tokens this contributor has used
+        if current_contribution == 0 {
+            let tokens_key = DataKey::ContributionTokens(project_id, user.clone());
+            let mut tokens: Vec<Address> = env
+                .storage()
+                .persistent()
+                .get(&tokens_key)
+                .unwrap_or(Vec::new(&env));
+            if !tokens.contains(&token_address) {
+                tokens.push_back(token_address.clone());
+                env.storage().persistent().set(&tokens_key, &tokens);
+            }
+        }
 
-        // Update project total deposited
-        project.total_deposited += amount;
-        env.storage()
-            .persistent()
-            .set(&DataKey::Project(project_id), &project);
+        Self::mark_contributed(&env, project_id, &user);
 
-        // Emit deposit event
-        events::DepositEvent {
+        // Emit token deposit event
+        events::TokenDepositEvent {
             user,
             project_id,
+            token_address,
             amount,
+            seq: Self::next_event_seq(&env),
         }
         .publish(&env);
 
         Ok(())
     }
 
-    /// Approve milestone for a project (admin only)
-    pub fn approve_milestone(
-        env: Env,
-        admin: Address,
+    /// Authorize a deposit and load its project, enforcing every precondition
+    fn check_deposit(
+        env: &Env,
+        user: &Address,
         project_id: u64,
-    ) -> Result<(), CrowdfundError> {
+        amount: i128,
+    ) -> Result<ProjectData, CrowdfundError> {
         // Check if contract is initialized
-        let stored_admin: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or(CrowdfundError::NotInitialized)?;
-
-        // Verify admin identity
-        if admin != stored_admin {
-            return Err(CrowdfundError::Unauthorized);
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(CrowdfundError::NotInitialized);
         }
 
-        // Require admin authorization
-        admin.require_auth();
+        // Require user authorization
+        user.require_auth();
 
         // Check Emergency Pause State
-        if Self::require_not_paused(&env) {
+        if Self::require_not_paused(env) {
             return Err(CrowdfundError::ContractPaused);
         };
 
-        // Check if project exists
-        if !env
+        // Validate amount
+        if amount <= 0 {
+            return Err(CrowdfundError::InvalidAmount);
+        }
+
+        // Get project
+        let project: ProjectData = env
             .storage()
             .persistent()
-            .has(&DataKey::Project(project_id))
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // Check if project is active
+        if !project.is_active {
+            return Err(CrowdfundError::ProjectNotActive);
+        }
+
+        // A project stops taking deposits once its deadline has passed
+        if Self::deadline_passed(env, &project) {
+            return Err(CrowdfundError::DeadlinePassed);
+        }
+
+        Self::check_min_reputation(env, &project, user)?;
+        Self::check_allowlist(env, project_id, user)?;
+        Self::check_deposit_cooldown(env, project_id, user)?;
+
+        Ok(project)
+    }
+
+    /// Reject a user missing from a project's allowlist while the allowlist is enabled
+    fn check_allowlist(env: &Env, project_id: u64, user: &Address) -> Result<(), CrowdfundError> {
+        if Self::is_allowlist_enabled(env.clone(), project_id)
+            && !Self::is_allowlisted(env.clone(), project_id, user.clone())
         {
-            return Err(CrowdfundError::ProjectNotFound);
+            return Err(CrowdfundError::NotAllowlisted);
         }
+        Ok(())
+    }
 
-        // Approve milestone
-        env.storage()
+    /// Reject a deposit made too soon after the user's previous one, then start a new cooldown
+    fn check_deposit_cooldown(
+        env: &Env,
+        project_id: u64,
+        user: &Address,
+    ) -> Result<(), CrowdfundError> {
+        let cooldown: u64 = env
+            .storage()
             .persistent()
-            .set(&DataKey::MilestoneApproved(project_id), &true);
+            .get(&DataKey::DepositCooldown(project_id))
+            .unwrap_or(0);
+        if cooldown == 0 {
+            return Ok(());
+        }
 
-        // Emit milestone approval event
-        events::MilestoneApprovedEvent { admin, project_id }.publish(&env);
+        let now = env.ledger().timestamp();
+        let last_deposit_key = DataKey::LastDepositTime(project_id, user.clone());
+        if let Some(last_deposit) = env.storage().persistent().get::<_, u64>(&last_deposit_key) {
+            if now < last_deposit.saturating_add(cooldown) {
+                return Err(CrowdfundError::DepositCooldownActive);
+            }
+        }
+        env.storage().persistent().set(&last_deposit_key, &now);
 
         Ok(())
     }
 
-    /// Withdraw funds from a project (owner only, requires milestone approval)
-    pub fn withdraw(env: Env, project_id: u64, amount: i128) -> Result<(), CrowdfundError> {
+    /// Lock funds against a commitment without revealing the target project.
+    ///
+    /// `commitment` must equal `sha256(project_id_be_bytes || salt)`. The funds are
+    /// attributed to the project only once [`reveal_deposit`] is called with the
+    /// matching project id and salt.
+    pub fn commit_deposit(
+        env: Env,
+        user: Address,
+        commitment: BytesN<32>,
+        token_address: Address,
+        amount: i128,
+    ) -> Result<(), CrowdfundError> {
         // Check if contract is initialized
         if !env.storage().instance().has(&DataKey::Admin) {
             return Err(CrowdfundError::NotInitialized);
         }
 
+        // Require user authorization
+        user.require_auth();
+
         // Check Emergency Pause State
         if Self::require_not_paused(&env) {
             return Err(CrowdfundError::ContractPaused);
         };
 
-        // Get project
-        let mut project: ProjectData = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Project(project_id))
-            .ok_or(CrowdfundError::ProjectNotFound)?;
-
-        // Require owner authorization
-        project.owner.require_auth();
-
-        // Check if project is active
-        if !project.is_active {
-            return Err(CrowdfundError::ProjectNotActive);
-        }
-
         // Validate amount
         if amount <= 0 {
             return Err(CrowdfundError::InvalidAmount);
         }
 
-        // Check milestone approval
-        let is_approved: bool = env
-            .storage()
-            .persistent()
-            .get(&DataKey::MilestoneApproved(project_id))
-            .unwrap_or(false);
+        // A commitment can only lock funds once
+        let commitment_key = DataKey::DepositCommitment(commitment.clone());
+        if env.storage().persistent().has(&commitment_key) {
+            return Err(CrowdfundError::CommitmentAlreadyExists);
+        }
+
+        // Lock tokens in the contract
+        let contract_address = env.current_contract_address();
+        token::transfer(&env, &token_address, &user, &contract_address, &amount)?;
+
+        // Store commitment
+        env.storage().persistent().set(
+            &commitment_key,
+            &DepositCommitment {
+                user: user.clone(),
+                token_address,
+                amount,
+            },
+        );
+
+        // Emit commitment event
+        events::DepositCommittedEvent {
+            user,
+            commitment,
+            amount,
+            seq: Self::next_event_seq(&env),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Reveal a previously committed deposit and attribute it to `project_id`
+    ///
+    /// The revealed amount goes through the same checks, fee and large-deposit
+    /// hold as `deposit`.
+    pub fn reveal_deposit(
+        env: Env,
+        user: Address,
+        project_id: u64,
+        salt: BytesN<32>,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(CrowdfundError::NotInitialized);
+        }
+
+        // Recompute the commitment from the revealed values
+        let mut preimage = Bytes::new(&env);
+        preimage.extend_from_array(&project_id.to_be_bytes());
+        preimage.extend_from_array(&salt.to_array());
+        let commitment: BytesN<32> = env.crypto().sha256(&preimage).into();
+
+        let commitment_key = DataKey::DepositCommitment(commitment.clone());
+        let committed: DepositCommitment = env
+            .storage()
+            .persistent()
+            .get(&commitment_key)
+            .ok_or(CrowdfundError::CommitmentNotFound)?;
+
+        // Only the committing user can reveal
+        if committed.user != user {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        let mut project = Self::check_deposit(&env, &user, project_id, committed.amount)?;
+
+        // Locked funds must be in the project's token
+        if committed.token_address != project.token_address {
+            return Err(CrowdfundError::TokenMismatch);
+        }
+
+        let (fee, net_amount) = Self::split_deposit_fee(&env, committed.amount)?;
+        Self::check_deposit_cap(&project, net_amount)?;
+
+        // Consume the commitment; its funds are already in the contract
+        env.storage().persistent().remove(&commitment_key);
+
+        Self::pay_deposit_fee(&env, &project.token_address, fee)?;
+        Self::credit_deposit(&env, &mut project, user.clone(), net_amount)?;
+
+        // Emit reveal event
+        events::DepositRevealedEvent {
+            user,
+            project_id,
+            commitment,
+            amount: committed.amount,
+            seq: Self::next_event_seq(&env),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Reject users below a project's reputation threshold, if it has one
+    fn check_min_reputation(
+        env: &Env,
+        project: &ProjectData,
+        user: &Address,
+    ) -> Result<(), CrowdfundError> {
+        if let Some(min_reputation) = project.min_reputation {
+            let reputation: i128 = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Reputation(user.clone()))
+                .unwrap_or(0);
+            if reputation < min_reputation {
+                return Err(CrowdfundError::ReputationTooLow);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject deposits that would push a project past its deposit cap, if it has one
+    fn check_deposit_cap(project: &ProjectData, amount: i128) -> Result<(), CrowdfundError> {
+        if let Some(max_total_deposit) = project.max_total_deposit {
+            let new_total = project
+                .total_deposited
+                .checked_add(amount)
+                .ok_or(CrowdfundError::Overflow)?;
+            if new_total > max_total_deposit {
+                return Err(CrowdfundError::DepositCapExceeded);
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the minimum time in seconds between one contributor's deposits to a project (owner only)
+    ///
+    /// Zero disables the cooldown.
+    pub fn set_deposit_cooldown(
+        env: Env,
+        project_id: u64,
+        cooldown: u64,
+    ) -> Result<(), CrowdfundError> {
+        // Get project
+        let project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // Require owner authorization
+        project.owner.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::DepositCooldown(project_id), &cooldown);
+
+        // Emit cooldown update event
+        if Self::full_events(&env) {
+            events::DepositCooldownUpdatedEvent {
+                project_id,
+                cooldown,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Get a project's deposit cooldown in seconds (0 when disabled)
+    pub fn get_deposit_cooldown(env: Env, project_id: u64) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::DepositCooldown(project_id))
+            .unwrap_or(0)
+    }
+
+    /// Restrict a project's deposits to its allowlist, or open them to everyone (owner only)
+    ///
+    /// Projects are open by default.
+    pub fn set_allowlist_enabled(
+        env: Env,
+        project_id: u64,
+        enabled: bool,
+    ) -> Result<(), CrowdfundError> {
+        // Get project
+        let project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // Require owner authorization
+        project.owner.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&ExtDataKey::AllowlistEnabled(project_id), &enabled);
+
+        // Emit allowlist update event
+        if Self::full_events(&env) {
+            events::AllowlistUpdatedEvent {
+                project_id,
+                enabled,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Add `contributor` to or remove them from a project's allowlist (owner only)
+    pub fn set_allowed(
+        env: Env,
+        project_id: u64,
+        contributor: Address,
+        allowed: bool,
+    ) -> Result<(), CrowdfundError> {
+        // Get project
+        let project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // Require owner authorization
+        project.owner.require_auth();
+
+        let allowed_key = ExtDataKey::ProjectAllowed(project_id, contributor.clone());
+        if allowed {
+            env.storage().persistent().set(&allowed_key, &true);
+        } else {
+            env.storage().persistent().remove(&allowed_key);
+        }
+
+        // Emit allowlist membership event
+        if Self::full_events(&env) {
+            events::ContributorAllowedEvent {
+                project_id,
+                contributor,
+                allowed,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Whether deposits to a project are restricted to its allowlist
+    pub fn is_allowlist_enabled(env: Env, project_id: u64) -> bool {
+        env.storage()
+            .persistent()
+            .get(&ExtDataKey::AllowlistEnabled(project_id))
+            .unwrap_or(false)
+    }
+
+    /// Whether `contributor` is on a project's allowlist
+    pub fn is_allowlisted(env: Env, project_id: u64, contributor: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&ExtDataKey::ProjectAllowed(project_id, contributor))
+    }
+
+    /// Set or clear a hard ceiling on a project's total deposits (owner only)
+    ///
+    /// The cap is independent of `target_amount` and must not be below it, so a
+    /// project can accept stretch funding up to a bounded amount.
+    pub fn set_deposit_cap(
+        env: Env,
+        project_id: u64,
+        max_total_deposit: Option<i128>,
+    ) -> Result<(), CrowdfundError> {
+        // Get project
+        let mut project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // Require owner authorization
+        project.owner.require_auth();
+
+        // Validate cap
+        if let Some(cap) = max_total_deposit {
+            if cap <= 0 || cap < project.target_amount {
+                return Err(CrowdfundError::InvalidAmount);
+            }
+        }
+
+        project.max_total_deposit = max_total_deposit;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Project(project_id), &project);
+
+        // Emit cap update event
+        if Self::full_events(&env) {
+            events::DepositCapUpdatedEvent {
+                project_id,
+                max_total_deposit,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Record that `user` has contributed to a project; this marker is never cleared
+    fn mark_contributed(env: &Env, project_id: u64, user: &Address) {
+        let has_contributed_key = DataKey::HasContributed(project_id, user.clone());
+        if !env.storage().persistent().has(&has_contributed_key) {
+            env.storage().persistent().set(&has_contributed_key, &true);
+
+            // Index the project under the contributor for donor-side lookups
+            let count_key = DataKey::DonorProjectCount(user.clone());
+            let count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+            env.storage().persistent().set(
+                &ExtDataKey::ContributorProject(user.clone(), count),
+                &project_id,
+            );
+            env.storage().persistent().set(&count_key, &(count + 1));
+        }
+    }
+
+    /// Record one deposit in the contributor's history, dropping the oldest entry when full
+    fn append_deposit_history(env: &Env, project_id: u64, user: &Address, amount: i128) {
+        let key = DataKey::DepositHistory(project_id, user.clone());
+        let mut history: Vec<(i128, u64)> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        if history.len() >= MAX_DEPOSIT_HISTORY {
+            history.pop_front();
+        }
+        history.push_back((amount, env.ledger().timestamp()));
+        env.storage().persistent().set(&key, &history);
+    }
+
+    /// Record funds leaving a project, dropping the oldest entry when full
+    fn append_withdraw_history(env: &Env, project_id: u64, amount: i128, to: &Address) {
+        let key = ExtDataKey::WithdrawHistory(project_id);
+        let mut history: Vec<(i128, u64, Address)> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        if history.len() >= MAX_WITHDRAW_HISTORY {
+            history.pop_front();
+        }
+        history.push_back((amount, env.ledger().timestamp(), to.clone()));
+        env.storage().persistent().set(&key, &history);
+    }
+
+    /// Swap one contributor's `old` root for the `new` one in the stored running sum
+    ///
+    /// Must be called after the new contribution is stored: if the sum was built
+    /// for a different exponent or sqrt precision it is rebuilt from scratch instead.
+    fn update_sum_sqrt(env: &Env, project_id: u64, token_address: &Address, old: i128, new: i128) {
+        let decimals = token::decimals(env, token_address);
+        let exponent = Self::get_match_exponent(env.clone());
+        let precision = Self::get_sqrt_precision(env.clone());
+        let key = DataKey::SumSqrt(project_id);
+
+        let sum = match env.storage().persistent().get::<_, (u32, u32, i128)>(&key) {
+            Some((stored_exponent, stored_precision, sum))
+                if stored_exponent == exponent && stored_precision == precision =>
+            {
+                let old_root = if old > 0 {
+                    root_scaled(normalize_amount(old, decimals), exponent, precision)
+                } else {
+                    0
+                };
+                let new_root = root_scaled(normalize_amount(new, decimals), exponent, precision);
+                saturating_sum(sum - old_root, new_root)
+            }
+            _ => Self::compute_sum_sqrt(env, project_id, decimals, exponent, precision),
+        };
+
+        env.storage()
+            .persistent()
+            .set(&key, &(exponent, precision, sum));
+    }
+
+    /// Recompute the sum of contribution roots for a project by walking every contributor
+    fn compute_sum_sqrt(
+        env: &Env,
+        project_id: u64,
+        decimals: u32,
+        exponent: u32,
+        precision: u32,
+    ) -> i128 {
+        let contributor_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ContributorCount(project_id))
+            .unwrap_or(0);
+
+        let mut sum = 0i128;
+        for i in 0..contributor_count {
+            let Some(contributor) = env
+                .storage()
+                .persistent()
+                .get::<_, Address>(&DataKey::Contributor(project_id, i))
+            else {
+                continue;
+            };
+            let contribution: i128 = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Contribution(project_id, contributor))
+                .unwrap_or(0);
+            if contribution > 0 {
+                sum = saturating_sum(
+                    sum,
+                    root_scaled(
+                        normalize_amount(contribution, decimals),
+                        exponent,
+                        precision,
+                    ),
+                );
+            }
+        }
+        sum
+    }
+
+    /// Credit a received deposit, or hold it for admin approval if it is large
+    fn credit_deposit(
+        env: &Env,
+        project: &mut ProjectData,
+        user: Address,
+        amount: i128,
+    ) -> Result<(), CrowdfundError> {
+        let project_id = project.id;
+
+        let threshold: Option<i128> = env
+            .storage()
+            .instance()
+            .get(&DataKey::LargeDepositThreshold);
+        if threshold.is_some_and(|threshold| amount >= threshold) {
+            let deposit_id: u64 = env
+                .storage()
+                .instance()
+                .get(&DataKey::NextPendingDepositId)
+                .unwrap_or(0);
+            env.storage()
+                .instance()
+                .set(&DataKey::NextPendingDepositId, &(deposit_id + 1));
+            env.storage().persistent().set(
+                &DataKey::PendingDeposit(deposit_id),
+                &PendingDeposit {
+                    user: user.clone(),
+                    project_id,
+                    token_address: project.token_address.clone(),
+                    amount,
+                },
+            );
+            Self::adjust_pending_deposits(env, project_id, 1);
+
+            // Emit held deposit event
+            events::DepositHeldEvent {
+                user,
+                project_id,
+                deposit_id,
+                amount,
+                seq: Self::next_event_seq(env),
+            }
+            .publish(env);
+
+            return Ok(());
+        }
+
+        Self::record_contribution(env, project, &user, amount)?;
+
+        // Emit deposit event
+        events::DepositEvent {
+            user,
+            project_id,
+            amount,
+            seq: Self::next_event_seq(env),
+        }
+        .publish(env);
+
+        Ok(())
+    }
+
+    /// Credit `amount` from `user` to a project's balance and contribution tracking
+    fn record_contribution(
+        env: &Env,
+        project: &mut ProjectData,
+        user: &Address,
+        amount: i128,
+    ) -> Result<(), CrowdfundError> {
+        let project_id = project.id;
+
+        // Track individual contribution for quadratic funding
+        let contribution_key = DataKey::Contribution(project_id, user.clone());
+        let current_contribution: i128 = env
+            .storage()
+            .persistent()
+            .get(&contribution_key)
+            .unwrap_or(0);
+        let new_contribution = current_contribution
+            .checked_add(amount)
+            .ok_or(CrowdfundError::Overflow)?;
+
+        // Update project balance
+        let balance_key = DataKey::ProjectBalance(project_id, project.token_address.clone());
+        let current_balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+        let new_balance = current_balance
+            .checked_add(amount)
+            .ok_or(CrowdfundError::Overflow)?;
+        env.storage().persistent().set(&balance_key, &new_balance);
+
+        // If this is a new contributor, add them to the contributors list
+        if current_contribution == 0 {
+            let contributor_count_key = DataKey::ContributorCount(project_id);
+            let contributor_count: u32 = env
+                .storage()
+                .persistent()
+                .get(&contributor_count_key)
+                .unwrap_or(0);
+
+            // Store contributor at index
+            env.storage()
+                .persistent()
+                .set(&DataKey::Contributor(project_id, contributor_count), user);
+
+            // Increment contributor count
+            env.storage()
+                .persistent()
+                .set(&contributor_count_key, &(contributor_count + 1));
+        }
+
+        Self::mark_contributed(env, project_id, user);
+        Self::append_deposit_history(env, project_id, user, amount);
+
+        // Update contribution amount
+        env.storage()
+            .persistent()
+            .set(&contribution_key, &new_contribution);
+        Self::update_sum_sqrt(
+            env,
+            project_id,
+            &project.token_address,
+            current_contribution,
+            new_contribution,
+        );
+
+        // Update project total deposited
+        project.total_deposited = project
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(CrowdfundError::Overflow)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Project(project_id), project);
+        Self::debug_check_balance(env, project_id);
+
+        // Emit a one-time event when the project first reaches its target
+        let target_reached_key = DataKey::TargetReached(project_id);
+        if project.target_amount > 0
+            && project.total_deposited >= project.target_amount
+            && !env.storage().persistent().has(&target_reached_key)
+        {
+            env.storage().persistent().set(&target_reached_key, &true);
+
+            if Self::full_events(env) {
+                events::TargetReachedEvent {
+                    project_id,
+                    total_deposited: project.total_deposited,
+                    timestamp: env.ledger().timestamp(),
+                    seq: Self::next_event_seq(env),
+                }
+                .publish(env);
+            }
+        }
+
+        // Top-ups are reported separately so analytics can tell them from first deposits
+        if current_contribution > 0 && Self::full_events(env) {
+            events::ContributionIncreasedEvent {
+                project_id,
+                contributor: user.clone(),
+                added: amount,
+                new_total: new_contribution,
+                seq: Self::next_event_seq(env),
+            }
+            .publish(env);
+        }
+
+        Ok(())
+    }
+
+    /// Submit a milestone for admin review (owner only)
+    pub fn submit_milestone(env: Env, project_id: u64, index: u32) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(CrowdfundError::NotInitialized);
+        }
+
+        // Check Emergency Pause State
+        if Self::require_not_paused(&env) {
+            return Err(CrowdfundError::ContractPaused);
+        };
+
+        // Get project
+        let project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // Require owner authorization
+        project.owner.require_auth();
+
+        // Check if project is active
+        if !project.is_active {
+            return Err(CrowdfundError::ProjectNotActive);
+        }
+
+        // Bound the number of milestones per project
+        if index >= MAX_MILESTONES {
+            return Err(CrowdfundError::TooManyMilestones);
+        }
+
+        // A milestone can only be submitted once
+        let milestone_key = DataKey::Milestone(project_id, index);
+        if env.storage().persistent().has(&milestone_key) {
+            return Err(CrowdfundError::MilestoneAlreadySubmitted);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&milestone_key, &MilestoneStatus::Submitted);
+
+        // Emit milestone submission event
+        if Self::full_events(&env) {
+            events::MilestoneSubmittedEvent {
+                owner: project.owner,
+                project_id,
+                index,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Approve a submitted milestone for a project (admin only)
+    pub fn approve_milestone(
+        env: Env,
+        admin: Address,
+        project_id: u64,
+        index: u32,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Check Emergency Pause State
+        if Self::require_not_paused(&env) {
+            return Err(CrowdfundError::ContractPaused);
+        };
+
+        // Check if project exists
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::Project(project_id))
+        {
+            return Err(CrowdfundError::ProjectNotFound);
+        }
+
+        // Only a pending submission can be approved
+        let milestone_key = DataKey::Milestone(project_id, index);
+        let status: Option<MilestoneStatus> = env.storage().persistent().get(&milestone_key);
+        if status != Some(MilestoneStatus::Submitted) {
+            return Err(CrowdfundError::MilestoneNotSubmitted);
+        }
+
+        // Approve milestone
+        env.storage()
+            .persistent()
+            .set(&milestone_key, &MilestoneStatus::Approved);
+        env.storage()
+            .persistent()
+            .set(&DataKey::MilestoneApproved(project_id), &true);
+
+        // Emit milestone approval event
+        if Self::full_events(&env) {
+            events::MilestoneApprovedEvent {
+                admin,
+                project_id,
+                index,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Reject a submitted or approved milestone (admin only)
+    ///
+    /// Rejecting an approved milestone blocks further withdrawals unless another
+    /// milestone is still approved; rejecting one that was only submitted leaves
+    /// withdrawals as they were. Funds the owner already withdrew are not clawed back.
+    pub fn reject_milestone(
+        env: Env,
+        admin: Address,
+        project_id: u64,
+        index: u32,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Check Emergency Pause State
+        if Self::require_not_paused(&env) {
+            return Err(CrowdfundError::ContractPaused);
+        };
+
+        // Check if project exists
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::Project(project_id))
+        {
+            return Err(CrowdfundError::ProjectNotFound);
+        }
+
+        // Only a pending or approved milestone can be rejected
+        let milestone_key = DataKey::Milestone(project_id, index);
+        let status: Option<MilestoneStatus> = env.storage().persistent().get(&milestone_key);
+        if !matches!(
+            status,
+            Some(MilestoneStatus::Submitted) | Some(MilestoneStatus::Approved)
+        ) {
+            return Err(CrowdfundError::MilestoneNotSubmitted);
+        }
+
+        // Reject milestone; withdrawing an approval re-evaluates the withdrawal gate
+        env.storage()
+            .persistent()
+            .set(&milestone_key, &MilestoneStatus::Rejected);
+        if status == Some(MilestoneStatus::Approved) {
+            let still_approved = (0..MAX_MILESTONES).any(|i| {
+                env.storage()
+                    .persistent()
+                    .get(&DataKey::Milestone(project_id, i))
+                    == Some(MilestoneStatus::Approved)
+            });
+            env.storage()
+                .persistent()
+                .set(&DataKey::MilestoneApproved(project_id), &still_approved);
+        }
+
+        // Emit milestone rejection event
+        if Self::full_events(&env) {
+            events::MilestoneRejectedEvent {
+                admin,
+                project_id,
+                index,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Withdraw funds from a project (owner only, requires milestone approval)
+    pub fn withdraw(env: Env, project_id: u64, amount: i128) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(CrowdfundError::NotInitialized);
+        }
+
+        let token_address = Self::get_project_token(env.clone(), project_id)?;
+        Self::withdraw_token(env, project_id, token_address, amount)
+    }
+
+    /// Withdraw funds held in a specific token (requires milestone approval)
+    ///
+    /// `amount` is limited to the project's balance in that token; deposits and
+    /// matches held in other tokens never back it. Only the project token counts
+    /// toward `total_withdrawn`. Once a project misses its target by the deadline,
+    /// its funds are left for contributor refunds.
+    pub fn withdraw_token(
+        env: Env,
+        project_id: u64,
+        token_address: Address,
+        amount: i128,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(CrowdfundError::NotInitialized);
+        }
+
+        // Check Emergency Pause State
+        if Self::require_not_paused(&env) {
+            return Err(CrowdfundError::ContractPaused);
+        };
+
+        // Get project
+        let mut project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // Require owner authorization
+        project.owner.require_auth();
+
+        // Check if project is active
+        if !project.is_active {
+            return Err(CrowdfundError::ProjectNotActive);
+        }
+
+        // Validate amount
+        if amount <= 0 {
+            return Err(CrowdfundError::InvalidAmount);
+        }
+
+        // Check milestone approval
+        let is_approved: bool = env
+            .storage()
+            .persistent()
+            .get(&DataKey::MilestoneApproved(project_id))
+            .unwrap_or(false);
 
         if !is_approved {
             return Err(CrowdfundError::MilestoneNotApproved);
         }
 
-        // Check balance
-        let balance_key = DataKey::ProjectBalance(project_id, project.token_address.clone());
-        let current_balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+        // Enforce the minimum project duration before any withdrawal
+        if !Self::min_duration_elapsed(&env, &project) {
+            return Err(CrowdfundError::WithdrawalTooEarly);
+        }
+
+        // Funds of a project that missed its target by the deadline are left for refunds
+        if Self::deadline_passed(&env, &project)
+            && project.target_amount > 0
+            && project.total_deposited < project.target_amount
+        {
+            return Err(CrowdfundError::DeadlinePassed);
+        }
+
+        // Check balance
+        let balance_key = DataKey::ProjectBalance(project_id, token_address.clone());
+        let current_balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+
+        if current_balance < amount {
+            return Err(CrowdfundError::InsufficientBalance);
+        }
+
+        // Transfer tokens from contract to owner
+        let contract_address = env.current_contract_address();
+        token::transfer(
+            &env,
+            &token_address,
+            &contract_address,
+            &project.owner,
+            &amount,
+        )?;
+
+        // Update project balance
+        env.storage()
+            .persistent()
+            .set(&balance_key, &(current_balance - amount));
+
+        if token_address != project.token_address {
+            // Emit token withdraw event
+            events::TokenWithdrawEvent {
+                owner: project.owner,
+                project_id,
+                token_address,
+                amount,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+            return Ok(());
+        }
+
+        // Update project total withdrawn
+        project.total_withdrawn += amount;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Project(project_id), &project);
+        Self::debug_check_balance(&env, project_id);
+        Self::append_withdraw_history(&env, project_id, amount, &project.owner);
+
+        // Emit withdraw event
+        events::WithdrawEvent {
+            owner: project.owner,
+            project_id,
+            amount,
+            seq: Self::next_event_seq(&env),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Whether the admin-set minimum duration has passed since the project was created
+    fn min_duration_elapsed(env: &Env, project: &ProjectData) -> bool {
+        let min_duration: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MinProjectDuration)
+            .unwrap_or(0);
+        env.ledger().timestamp() >= project.created_at.saturating_add(min_duration)
+    }
+
+    /// Withdraw the project's entire balance (requires milestone approval)
+    ///
+    /// Returns the amount withdrawn. Subject to the same checks as `withdraw`.
+    pub fn withdraw_all(env: Env, project_id: u64) -> Result<i128, CrowdfundError> {
+        let amount = Self::get_balance(env.clone(), project_id)?;
+        Self::withdraw(env, project_id, amount)?;
+        Ok(amount)
+    }
+
+    /// Switch the token a project accepts (owner only, before any deposit)
+    pub fn set_project_token(
+        env: Env,
+        project_id: u64,
+        new_token: Address,
+    ) -> Result<(), CrowdfundError> {
+        // Get project
+        let mut project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // Require owner authorization
+        project.owner.require_auth();
+
+        // Only projects that have never received funds may change token
+        if project.total_deposited != 0 {
+            return Err(CrowdfundError::ProjectHasDeposits);
+        }
+
+        if !token::is_token(&env, &new_token) {
+            return Err(CrowdfundError::InvalidToken);
+        }
+
+        // Held deposits are owed in the current token
+        if Self::pending_deposits(&env, project_id) > 0 {
+            return Err(CrowdfundError::ProjectHasDeposits);
+        }
+
+        // A separate matching token must keep sharing the project token's decimals
+        let matching_token = Self::matching_token(&env, &project);
+        if matching_token != project.token_address
+            && token::decimals(&env, &matching_token) != token::decimals(&env, &new_token)
+        {
+            return Err(CrowdfundError::DecimalsMismatch);
+        }
+
+        // Secondary deposits already held in the new token would otherwise become
+        // withdrawable project funds without counting toward `total_deposited`
+        let held: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ProjectBalance(project_id, new_token.clone()))
+            .unwrap_or(0);
+        if held != 0 {
+            return Err(CrowdfundError::ProjectHasDeposits);
+        }
+
+        // Reservations are held in the old token's pool
+        Self::set_reserved_match(&env, &project, 0);
+
+        // Move the (empty) balance entry to the new token
+        let old_token = project.token_address.clone();
+        env.storage()
+            .persistent()
+            .remove(&DataKey::ProjectBalance(project_id, old_token.clone()));
+        env.storage().persistent().set(
+            &DataKey::ProjectBalance(project_id, new_token.clone()),
+            &0i128,
+        );
+
+        project.token_address = new_token.clone();
+        env.storage()
+            .persistent()
+            .set(&DataKey::Project(project_id), &project);
+
+        // Emit token change event
+        if Self::full_events(&env) {
+            events::ProjectTokenChangedEvent {
+                project_id,
+                old_token,
+                new_token,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Set a project's funding deadline as a ledger timestamp (owner only, once)
+    pub fn set_deadline(env: Env, project_id: u64, end_time: u64) -> Result<(), CrowdfundError> {
+        // Get project
+        let mut project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // Require owner authorization
+        project.owner.require_auth();
+
+        if project.end_time.is_some() {
+            return Err(CrowdfundError::DeadlineAlreadySet);
+        }
+
+        // Validate deadline
+        if end_time <= env.ledger().timestamp() {
+            return Err(CrowdfundError::InvalidDeadline);
+        }
+
+        project.end_time = Some(end_time);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Project(project_id), &project);
+
+        // Emit deadline event
+        if Self::full_events(&env) {
+            events::DeadlineSetEvent {
+                project_id,
+                end_time,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Push a project's deadline later (admin or owner)
+    ///
+    /// Deadlines can only move forward so contributors are never stranded by a
+    /// campaign ending earlier than announced.
+    pub fn extend_deadline(
+        env: Env,
+        caller: Address,
+        project_id: u64,
+        new_end_time: u64,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Get project
+        let mut project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // Verify caller is the admin or the project owner
+        if caller != stored_admin && caller != project.owner {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require caller authorization
+        caller.require_auth();
+
+        // Only an existing deadline can be moved, and only forward
+        let old_end_time = project.end_time.ok_or(CrowdfundError::InvalidDeadline)?;
+        if new_end_time <= old_end_time {
+            return Err(CrowdfundError::InvalidDeadline);
+        }
+
+        project.end_time = Some(new_end_time);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Project(project_id), &project);
+
+        // Emit deadline extension event
+        if Self::full_events(&env) {
+            events::DeadlineExtendedEvent {
+                project_id,
+                old_end_time,
+                new_end_time,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Check whether a project's contributors would currently be eligible for a refund
+    ///
+    /// A project is refundable when it has ended, either because its deadline passed
+    /// or it was closed, without meeting a non-zero target while still holding funds.
+    pub fn is_refundable(env: Env, project_id: u64) -> Result<bool, CrowdfundError> {
+        // Get project
+        let project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        let ended = Self::deadline_passed(&env, &project) || !project.is_active;
+        let underfunded =
+            project.target_amount > 0 && project.total_deposited < project.target_amount;
+
+        let balance: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ProjectBalance(
+                project_id,
+                project.token_address.clone(),
+            ))
+            .unwrap_or(0);
+
+        Ok(ended && underfunded && balance > 0)
+    }
+
+    /// Whether a project's deadline, if it has one, is behind the current ledger time
+    fn deadline_passed(env: &Env, project: &ProjectData) -> bool {
+        project
+            .end_time
+            .is_some_and(|end_time| env.ledger().timestamp() > end_time)
+    }
+
+    /// Get the amount `contributor` could reclaim from a project right now
+    ///
+    /// This is their tracked contribution while `is_refundable` holds, else 0.
+    pub fn get_refundable(
+        env: Env,
+        project_id: u64,
+        contributor: Address,
+    ) -> Result<i128, CrowdfundError> {
+        if !Self::is_refundable(env.clone(), project_id)? {
+            return Ok(0);
+        }
+
+        Ok(env
+            .storage()
+            .persistent()
+            .get(&DataKey::Contribution(project_id, contributor))
+            .unwrap_or(0))
+    }
+
+    /// Reclaim a contributor's deposit from a refundable project
+    ///
+    /// Pays out the tracked contribution, capped at what the project still holds.
+    pub fn claim_refund(
+        env: Env,
+        contributor: Address,
+        project_id: u64,
+    ) -> Result<i128, CrowdfundError> {
+        // Check Emergency Pause State
+        if Self::require_not_paused(&env) {
+            return Err(CrowdfundError::ContractPaused);
+        };
+
+        contributor.require_auth();
+
+        Self::refund_contribution(&env, &contributor, project_id)
+    }
+
+    /// Reclaim a contributor's deposits from several projects in one call
+    ///
+    /// Projects that aren't refundable for `contributor` are skipped rather than
+    /// failing the call. Returns the total refunded.
+    pub fn batch_claim_refund(
+        env: Env,
+        contributor: Address,
+        project_ids: Vec<u64>,
+    ) -> Result<i128, CrowdfundError> {
+        // Check Emergency Pause State
+        if Self::require_not_paused(&env) {
+            return Err(CrowdfundError::ContractPaused);
+        };
+
+        contributor.require_auth();
+
+        let mut total: i128 = 0;
+        for project_id in project_ids.iter() {
+            if let Ok(refunded) = Self::refund_contribution(&env, &contributor, project_id) {
+                total += refunded;
+            }
+        }
+
+        Ok(total)
+    }
+
+    /// Pay `contributor` back from a refundable project
+    ///
+    /// Validates everything before moving funds, so an error leaves no partial state
+    /// behind for `batch_claim_refund` to skip over.
+    fn refund_contribution(
+        env: &Env,
+        contributor: &Address,
+        project_id: u64,
+    ) -> Result<i128, CrowdfundError> {
+        if !Self::is_refundable(env.clone(), project_id)? {
+            return Err(CrowdfundError::NotRefundable);
+        }
+
+        let mut project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        let contribution_key = DataKey::Contribution(project_id, contributor.clone());
+        let contribution: i128 = env
+            .storage()
+            .persistent()
+            .get(&contribution_key)
+            .unwrap_or(0);
+        let balance_key = DataKey::ProjectBalance(project_id, project.token_address.clone());
+        let balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+        let amount = contribution.min(balance);
+        if amount <= 0 {
+            return Err(CrowdfundError::NotRefundable);
+        }
+
+        token::transfer(
+            env,
+            &project.token_address,
+            &env.current_contract_address(),
+            contributor,
+            &amount,
+        )?;
+
+        env.storage()
+            .persistent()
+            .set(&balance_key, &(balance - amount));
+        env.storage().persistent().set(&contribution_key, &0i128);
+        Self::update_sum_sqrt(env, project_id, &project.token_address, contribution, 0);
+
+        // A refund leaves the project like a withdrawal
+        project.total_withdrawn += amount;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Project(project_id), &project);
+        Self::debug_check_balance(env, project_id);
+        Self::append_withdraw_history(env, project_id, amount, contributor);
+
+        // Emit refund event
+        events::RefundClaimedEvent {
+            contributor: contributor.clone(),
+            project_id,
+            amount,
+            seq: Self::next_event_seq(env),
+        }
+        .publish(env);
+
+        Ok(amount)
+    }
+
+    /// Close a project so it no longer accepts deposits or withdrawals (admin only)
+    pub fn close_project(env: Env, admin: Address, project_id: u64) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Get project
+        let mut project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        if !project.is_active {
+            return Err(CrowdfundError::ProjectNotActive);
+        }
+
+        project.is_active = false;
+        project.closed_at = Some(env.ledger().timestamp());
+
+        // A closed project can't be matched, so its reservation goes back to the pool
+        Self::set_reserved_match(&env, &project, 0);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Project(project_id), &project);
+        let active_count = Self::get_active_project_count(env.clone());
+        env.storage().instance().set(
+            &ExtDataKey::ActiveProjectCount,
+            &active_count.saturating_sub(1),
+        );
+
+        // Emit project closure event
+        if Self::full_events(&env) {
+            events::ProjectClosedEvent {
+                admin,
+                project_id,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Sweep a closed project's residual balance below `DUST_THRESHOLD` to `to` (admin only)
+    pub fn sweep_dust(
+        env: Env,
+        admin: Address,
+        project_id: u64,
+        to: Address,
+    ) -> Result<i128, CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Get project
+        let project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // Only closed projects can be swept
+        if project.is_active {
+            return Err(CrowdfundError::ProjectStillActive);
+        }
+
+        let balance_key = DataKey::ProjectBalance(project_id, project.token_address.clone());
+        let dust: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+
+        if dust >= DUST_THRESHOLD {
+            return Err(CrowdfundError::BalanceAboveDustThreshold);
+        }
+
+        if dust > 0 {
+            token::transfer(
+                &env,
+                &project.token_address,
+                &env.current_contract_address(),
+                &to,
+                &dust,
+            )?;
+        }
+
+        env.storage().persistent().set(&balance_key, &0i128);
+
+        // Swept dust leaves the project like a withdrawal
+        let mut project = project;
+        project.total_withdrawn += dust;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Project(project_id), &project);
+        Self::debug_check_balance(&env, project_id);
+        if dust > 0 {
+            Self::append_withdraw_history(&env, project_id, dust, &to);
+        }
+
+        // Emit sweep event
+        events::DustSweptEvent {
+            project_id,
+            to,
+            amount: dust,
+            seq: Self::next_event_seq(&env),
+        }
+        .publish(&env);
+
+        Ok(dust)
+    }
+
+    /// Register a new contributor
+    ///
+    /// The registration fee, if any, is taken in the same invocation as the
+    /// registration itself. Any error after the fee transfer rolls the whole call
+    /// back, transfer included, so a failed registration never keeps the fee.
+    pub fn register_contributor(env: Env, contributor: Address) -> Result<(), CrowdfundError> {
+        // Require contributor authorization
+        contributor.require_auth();
+
+        // Check if already registered
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::RegisteredContributor(contributor.clone()))
+        {
+            return Err(CrowdfundError::AlreadyRegistered);
+        }
+
+        // Charge the registration fee, if one is configured
+        let fee: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RegistrationFee)
+            .unwrap_or(0);
+        if fee > 0 {
+            let fee_token: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::FeeToken)
+                .ok_or(CrowdfundError::NotInitialized)?;
+            let collector: Address = env
+                .storage()
+                .instance()
+                .get(&ExtDataKey::RegistrationFeeCollector)
+                .ok_or(CrowdfundError::NotInitialized)?;
+            token::transfer(&env, &fee_token, &contributor, &collector, &fee)?;
+        }
+
+        // Store registration
+        env.storage()
+            .persistent()
+            .set(&DataKey::RegisteredContributor(contributor.clone()), &true);
+
+        // Initialize reputation
+        env.storage()
+            .persistent()
+            .set(&DataKey::Reputation(contributor.clone()), &0i128);
+
+        // Index the contributor in registration order
+        let registered_count = Self::get_registered_contributor_count(env.clone());
+        env.storage().persistent().set(
+            &ExtDataKey::RegisteredContributorAt(registered_count),
+            &contributor,
+        );
+        env.storage().instance().set(
+            &DataKey::RegisteredContributorCount,
+            &(registered_count + 1),
+        );
+        Self::rank_reputation(&env, &contributor, 0);
+
+        // Emit registration event
+        if Self::full_events(&env) {
+            events::ContributorRegisteredEvent {
+                contributor,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Set the fee charged by `register_contributor` (admin only)
+    ///
+    /// The fee is paid in `fee_token` to `collector`, which only receives registration
+    /// fees. A zero fee keeps registration free.
+    pub fn set_registration_fee(
+        env: Env,
+        admin: Address,
+        fee: i128,
+        fee_token: Address,
+        collector: Address,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Validate fee
+        if fee < 0 {
+            return Err(CrowdfundError::InvalidFee);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::RegistrationFee, &fee);
+        env.storage().instance().set(&DataKey::FeeToken, &fee_token);
+        env.storage()
+            .instance()
+            .set(&ExtDataKey::RegistrationFeeCollector, &collector);
+
+        // Emit fee update event
+        if Self::full_events(&env) {
+            events::RegistrationFeeUpdatedEvent {
+                admin,
+                fee,
+                fee_token,
+                collector,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Get the fee charged by `register_contributor`
+    pub fn get_registration_fee(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::RegistrationFee)
+            .unwrap_or(0)
+    }
+
+    /// Update contributor reputation (admin only for now, or could be internal)
+    pub fn update_reputation(
+        env: Env,
+        admin: Address,
+        contributor: Address,
+        change: i128,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Check if contributor is registered
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::RegisteredContributor(contributor.clone()))
+        {
+            return Err(CrowdfundError::ContributorNotFound);
+        }
+
+        // Get current reputation
+        let old_reputation: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Reputation(contributor.clone()))
+            .unwrap_or(0);
+        let new_reputation = old_reputation + change;
+
+        // Store new reputation
+        env.storage()
+            .persistent()
+            .set(&DataKey::Reputation(contributor.clone()), &new_reputation);
+        Self::rank_reputation(&env, &contributor, new_reputation);
+
+        // Emit reputation change event
+        if Self::full_events(&env) {
+            events::ReputationUpdatedEvent {
+                contributor,
+                old_reputation,
+                new_reputation,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Update the reputation of several contributors in one call (admin only)
+    ///
+    /// `contributors` and `changes` are paired by index. Every contributor must be
+    /// registered; otherwise no reputation is changed.
+    pub fn batch_update_reputation(
+        env: Env,
+        admin: Address,
+        contributors: Vec<Address>,
+        changes: Vec<i128>,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        if contributors.len() != changes.len() {
+            return Err(CrowdfundError::LengthMismatch);
+        }
+
+        // Validate every contributor before applying any change
+        for contributor in contributors.iter() {
+            if !env
+                .storage()
+                .persistent()
+                .has(&DataKey::RegisteredContributor(contributor))
+            {
+                return Err(CrowdfundError::ContributorNotFound);
+            }
+        }
+
+        for (contributor, change) in contributors.iter().zip(changes.iter()) {
+            let old_reputation: i128 = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Reputation(contributor.clone()))
+                .unwrap_or(0);
+            let new_reputation = old_reputation + change;
+
+            env.storage()
+                .persistent()
+                .set(&DataKey::Reputation(contributor.clone()), &new_reputation);
+            Self::rank_reputation(&env, &contributor, new_reputation);
+
+            if Self::full_events(&env) {
+                events::ReputationUpdatedEvent {
+                    contributor,
+                    old_reputation,
+                    new_reputation,
+                    seq: Self::next_event_seq(&env),
+                }
+                .publish(&env);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check whether an address is a registered contributor
+    pub fn is_registered(env: Env, contributor: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::RegisteredContributor(contributor))
+    }
+
+    /// Get contributor reputation
+    pub fn get_reputation(env: Env, contributor: Address) -> Result<i128, CrowdfundError> {
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::RegisteredContributor(contributor.clone()))
+        {
+            return Err(CrowdfundError::ContributorNotFound);
+        }
+        Ok(env
+            .storage()
+            .persistent()
+            .get(&DataKey::Reputation(contributor))
+            .unwrap_or(0))
+    }
+
+    /// Get the number of registered contributors
+    pub fn get_registered_contributor_count(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::RegisteredContributorCount)
+            .unwrap_or(0)
+    }
+
+    /// Get the `n` registered contributors with the highest reputation, highest first
+    ///
+    /// Reads the leaderboard kept by `update_reputation` and
+    /// `batch_update_reputation`, so `n` is capped at `MAX_LEADERBOARD_SIZE`.
+    /// Ties rank whoever reached the score first. A ranked contributor whose
+    /// reputation falls keeps their slot until someone outscores them.
+    pub fn get_top_reputation(env: Env, n: u32) -> Vec<(Address, i128)> {
+        let mut top: Vec<(Address, i128)> = env
+            .storage()
+            .persistent()
+            .get(&ExtDataKey::TopReputation)
+            .unwrap_or(Vec::new(&env));
+        let n = n.min(MAX_LEADERBOARD_SIZE);
+        while top.len() > n {
+            top.pop_back();
+        }
+        top
+    }
+
+    /// Move `contributor` to their place on the leaderboard for `reputation`
+    ///
+    /// The leaderboard is sorted highest first and holds at most
+    /// `MAX_LEADERBOARD_SIZE` entries; a score that doesn't beat the last entry
+    /// of a full board is left off.
+    fn rank_reputation(env: &Env, contributor: &Address, reputation: i128) {
+        let key = ExtDataKey::TopReputation;
+        let mut top: Vec<(Address, i128)> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+
+        if let Some(index) = top.iter().position(|(ranked, _)| ranked == *contributor) {
+            top.remove(index as u32);
+        }
+        if top.len() >= MAX_LEADERBOARD_SIZE
+            && reputation <= top.get_unchecked(MAX_LEADERBOARD_SIZE - 1).1
+        {
+            return;
+        }
+
+        let mut index = top.len();
+        while index > 0 && top.get_unchecked(index - 1).1 < reputation {
+            index -= 1;
+        }
+        top.insert(index, (contributor.clone(), reputation));
+        if top.len() > MAX_LEADERBOARD_SIZE {
+            top.pop_back();
+        }
+        env.storage().persistent().set(&key, &top);
+    }
+
+    /// Get project data
+    pub fn get_project(env: Env, project_id: u64) -> Result<ProjectData, CrowdfundError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)
+    }
+
+    /// Get the number of projects ever created
+    pub fn get_project_count(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::NextProjectId)
+            .unwrap_or(0)
+    }
+
+    /// Get the number of projects that haven't been closed
+    pub fn get_active_project_count(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&ExtDataKey::ActiveProjectCount)
+            .unwrap_or(0)
+    }
+
+    /// Get summaries of up to `limit` projects, starting at id `start`
+    ///
+    /// `limit` is capped at `MAX_PAGE_SIZE`; an empty page means `start` is past
+    /// the last project.
+    pub fn get_projects_summary(env: Env, start: u64, limit: u32) -> Vec<ProjectSummary> {
+        let project_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextProjectId)
+            .unwrap_or(0);
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE) as u64)
+            .min(project_count);
+
+        let mut summaries = Vec::new(&env);
+        for project_id in start..end {
+            if let Some(summary) = Self::project_summary(&env, project_id) {
+                summaries.push_back(summary);
+            }
+        }
+        summaries
+    }
+
+    /// Get summaries of up to `limit` projects in `category`, skipping the first `start`
+    ///
+    /// `limit` is capped at `MAX_PAGE_SIZE`.
+    pub fn get_projects_by_category(
+        env: Env,
+        category: Symbol,
+        start: u32,
+        limit: u32,
+    ) -> Vec<ProjectSummary> {
+        let category_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CategoryProjectCount(category.clone()))
+            .unwrap_or(0);
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(category_count);
+
+        let mut summaries = Vec::new(&env);
+        for i in start..end {
+            let Some(project_id) = env
+                .storage()
+                .persistent()
+                .get::<_, u64>(&ExtDataKey::CategoryProject(category.clone(), i))
+            else {
+                continue;
+            };
+            if let Some(summary) = Self::project_summary(&env, project_id) {
+                summaries.push_back(summary);
+            }
+        }
+        summaries
+    }
+
+    /// Build the listing card for a project, if it exists
+    fn project_summary(env: &Env, project_id: u64) -> Option<ProjectSummary> {
+        let project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))?;
+        let balance: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ProjectBalance(
+                project_id,
+                project.token_address.clone(),
+            ))
+            .unwrap_or(0);
+        let contributor_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ContributorCount(project_id))
+            .unwrap_or(0);
+        let total_matched: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TotalMatched(project_id))
+            .unwrap_or(0);
+
+        Some(ProjectSummary {
+            id: project_id,
+            owner: project.owner,
+            name: project.name,
+            category: project.category,
+            token_address: project.token_address,
+            target_amount: project.target_amount,
+            total_deposited: project.total_deposited,
+            balance,
+            contributor_count,
+            total_matched,
+            is_active: project.is_active,
+        })
+    }
+
+    /// Get the ledger timestamp at which a project was created
+    pub fn get_project_created_at(env: Env, project_id: u64) -> Result<u64, CrowdfundError> {
+        let ProjectData { created_at, .. } = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        Ok(created_at)
+    }
+
+    /// Get the token a project accepts
+    pub fn get_project_token(env: Env, project_id: u64) -> Result<Address, CrowdfundError> {
+        let ProjectData { token_address, .. } = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        Ok(token_address)
+    }
+
+    /// Get project balance
+    pub fn get_balance(env: Env, project_id: u64) -> Result<i128, CrowdfundError> {
+        // Get project to get token address
+        let ProjectData { token_address, .. } = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        let balance_key = DataKey::ProjectBalance(project_id, token_address);
+        Ok(env.storage().persistent().get(&balance_key).unwrap_or(0))
+    }
+
+    /// Get a project's balance for an explicit token
+    pub fn get_balance_for_token(
+        env: Env,
+        project_id: u64,
+        token_address: Address,
+    ) -> Result<i128, CrowdfundError> {
+        // Check if project exists
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::Project(project_id))
+        {
+            return Err(CrowdfundError::ProjectNotFound);
+        }
+
+        let balance_key = DataKey::ProjectBalance(project_id, token_address);
+        Ok(env.storage().persistent().get(&balance_key).unwrap_or(0))
+    }
+
+    /// Get a project's funds as `(deposited, matched, withdrawn)` in the project token
+    ///
+    /// `deposited` excludes matching funds, which `total_deposited` includes, so
+    /// the balance is `deposited + matched - withdrawn`. `matched` also counts
+    /// matches paid in a separate matching token; those are held in that
+    /// token's balance instead, so the identity then holds per token.
+    pub fn get_balance_breakdown(
+        env: Env,
+        project_id: u64,
+    ) -> Result<(i128, i128, i128), CrowdfundError> {
+        // Get project
+        let project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        let matched: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TotalMatched(project_id))
+            .unwrap_or(0);
+        let matched_in_project_token =
+            Self::matched_in_token(&env, project_id, &project.token_address);
+
+        Ok((
+            project.total_deposited - matched_in_project_token,
+            matched,
+            project.total_withdrawn,
+        ))
+    }
+
+    /// Get the amount the project owner could withdraw right now
+    ///
+    /// Returns the project balance when the project is active, its milestone is
+    /// approved and the contract is not paused; otherwise zero.
+    pub fn get_withdrawable(env: Env, project_id: u64) -> Result<i128, CrowdfundError> {
+        let project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        let is_approved: bool = env
+            .storage()
+            .persistent()
+            .get(&DataKey::MilestoneApproved(project_id))
+            .unwrap_or(false);
+
+        if !project.is_active
+            || !is_approved
+            || !Self::min_duration_elapsed(&env, &project)
+            || Self::require_not_paused(&env)
+        {
+            return Ok(0);
+        }
+
+        let balance_key = DataKey::ProjectBalance(project_id, project.token_address);
+        Ok(env.storage().persistent().get(&balance_key).unwrap_or(0))
+    }
+
+    /// Check if milestone is approved for a project
+    pub fn is_milestone_approved(env: Env, project_id: u64) -> Result<bool, CrowdfundError> {
+        // Check if project exists
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::Project(project_id))
+        {
+            return Err(CrowdfundError::ProjectNotFound);
+        }
+
+        Ok(env
+            .storage()
+            .persistent()
+            .get(&DataKey::MilestoneApproved(project_id))
+            .unwrap_or(false))
+    }
+
+    /// Check if the milestone at `index` is approved for a project
+    ///
+    /// `is_milestone_approved` only says whether withdrawals are open; this reads
+    /// the status of one milestone, e.g. to render a checklist.
+    pub fn is_milestone_approved_at(
+        env: Env,
+        project_id: u64,
+        index: u32,
+    ) -> Result<bool, CrowdfundError> {
+        // Check if project exists
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::Project(project_id))
+        {
+            return Err(CrowdfundError::ProjectNotFound);
+        }
+
+        let status: Option<MilestoneStatus> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Milestone(project_id, index));
+        Ok(status == Some(MilestoneStatus::Approved))
+    }
+
+    /// Get admin address
+    pub fn get_admin(env: Env) -> Result<Address, CrowdfundError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)
+    }
+
+    /// Fund the matching pool (admin only)
+    pub fn fund_matching_pool(
+        env: Env,
+        admin: Address,
+        token_address: Address,
+        amount: i128,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Validate amount
+        if amount <= 0 {
+            return Err(CrowdfundError::InvalidAmount);
+        }
+
+        // Accounting-only: update internal matching pool balance without transferring tokens
+
+        // Update matching pool balance
+        let pool_key = DataKey::MatchingPool(token_address.clone());
+        let current_pool: i128 = env.storage().persistent().get(&pool_key).unwrap_or(0);
+        let new_pool = current_pool
+            .checked_add(amount)
+            .ok_or(CrowdfundError::Overflow)?;
+        env.storage().persistent().set(&pool_key, &new_pool);
+
+        // Emit pool funding event; the admin credits the pool directly
+        events::MatchingPoolFundedEvent {
+            donor: admin,
+            token_address,
+            amount,
+            seq: Self::next_event_seq(&env),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Add `amount` of `token_address` to its matching pool from any donor
+    pub fn contribute_to_matching_pool(
+        env: Env,
+        donor: Address,
+        token_address: Address,
+        amount: i128,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(CrowdfundError::NotInitialized);
+        }
+
+        // Require donor authorization
+        donor.require_auth();
+
+        // Check Emergency Pause State
+        if Self::require_not_paused(&env) {
+            return Err(CrowdfundError::ContractPaused);
+        };
+
+        // Validate amount
+        if amount <= 0 {
+            return Err(CrowdfundError::InvalidAmount);
+        }
+
+        let pool_key = DataKey::MatchingPool(token_address.clone());
+        let current_pool: i128 = env.storage().persistent().get(&pool_key).unwrap_or(0);
+        let new_pool = current_pool
+            .checked_add(amount)
+            .ok_or(CrowdfundError::Overflow)?;
+
+        // Pull tokens from the donor into the contract
+        token::transfer(
+            &env,
+            &token_address,
+            &donor,
+            &env.current_contract_address(),
+            &amount,
+        )?;
+
+        // Update matching pool balance
+        env.storage().persistent().set(&pool_key, &new_pool);
+
+        // Emit pool funding event
+        events::MatchingPoolFundedEvent {
+            donor,
+            token_address,
+            amount,
+            seq: Self::next_event_seq(&env),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Mint tokens straight into the matching pool (admin only)
+    ///
+    /// For deployments where this contract administers the pool token: mints
+    /// `amount` to the contract and credits it to the pool in one call.
+    pub fn mint_to_matching_pool(
+        env: Env,
+        admin: Address,
+        token_address: Address,
+        amount: i128,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Validate amount
+        if amount <= 0 {
+            return Err(CrowdfundError::InvalidAmount);
+        }
+
+        token::mint(
+            &env,
+            &token_address,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        // Update matching pool balance
+        let pool_key = DataKey::MatchingPool(token_address.clone());
+        let current_pool: i128 = env.storage().persistent().get(&pool_key).unwrap_or(0);
+        let new_pool = current_pool
+            .checked_add(amount)
+            .ok_or(CrowdfundError::Overflow)?;
+        env.storage().persistent().set(&pool_key, &new_pool);
+
+        // Emit pool funding event; the admin funds the pool by minting
+        events::MatchingPoolFundedEvent {
+            donor: admin,
+            token_address,
+            amount,
+            seq: Self::next_event_seq(&env),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Calculate matching funds for a project using quadratic funding formula
+    /// Formula: (sum of sqrt(contributions))^2
+    /// Returns the amount of matching funds based on number of unique contributors and amounts
+    ///
+    /// `ContributorCount` only ever grows; a contributor whose contribution has been
+    /// zeroed keeps their index but is skipped here, so they no longer affect the match.
+    pub fn calculate_match(env: Env, project_id: u64) -> Result<i128, CrowdfundError> {
+        // Check if contract is initialized
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(CrowdfundError::NotInitialized);
+        }
+
+        // Get contributor count
+        let contributor_count_key = DataKey::ContributorCount(project_id);
+        let contributor_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&contributor_count_key)
+            .unwrap_or(0);
+
+        if contributor_count == 0 {
+            return Ok(0);
+        }
+
+        // Normalize amounts by the project token's decimals so the math behaves the
+        // same for every token and high-decimal amounts don't saturate
+        let ProjectData { token_address, .. } = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+        let decimals = token::decimals(&env, &token_address);
+
+        let penalty_bps: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SybilPenaltyBps)
+            .unwrap_or(0);
+        let exponent: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MatchExponent)
+            .unwrap_or(DEFAULT_MATCH_EXPONENT);
+        let precision = Self::get_sqrt_precision(env.clone());
+
+        // Without a sybil penalty each root is independent of the others, so the
+        // running sum kept by deposits can be used directly
+        if penalty_bps == 0 {
+            let sum_root_scaled = match env
+                .storage()
+                .persistent()
+                .get::<_, (u32, u32, i128)>(&DataKey::SumSqrt(project_id))
+            {
+                Some((stored_exponent, stored_precision, sum))
+                    if stored_exponent == exponent && stored_precision == precision =>
+                {
+                    sum
+                }
+                _ => Self::compute_sum_sqrt(&env, project_id, decimals, exponent, precision),
+            };
+            let match_amount = unscale(saturating_pow_scaled(sum_root_scaled, exponent));
+            return Ok(denormalize_amount(match_amount, decimals));
+        }
+
+        // Collect positive contributions
+        let mut contributions: Vec<i128> = Vec::new(&env);
+
+        // Iterate through all contributors
+        for i in 0..contributor_count {
+            let contributor_key = DataKey::Contributor(project_id, i);
+            let contributor: Address = env
+                .storage()
+                .persistent()
+                .get(&contributor_key)
+                .ok_or(CrowdfundError::ProjectNotFound)?;
+
+            // Get contribution amount
+            let contribution_key = DataKey::Contribution(project_id, contributor);
+            let contribution: i128 = env
+                .storage()
+                .persistent()
+                .get(&contribution_key)
+                .unwrap_or(0);
+
+            if contribution > 0 {
+                contributions.push_back(contribution);
+            }
+        }
+
+        // Count identical amounts for the sybil penalty
+        let mut amount_counts: Map<i128, u32> = Map::new(&env);
+        for contribution in contributions.iter() {
+            let count = amount_counts.get(contribution).unwrap_or(0);
+            amount_counts.set(contribution, count + 1);
+        }
+
+        // Sum of k-th roots of contributions
+        let mut sum_root_scaled = 0i128;
+        for contribution in contributions.iter() {
+            // Calculate contribution^(1/k) scaled
+            let mut root_contribution_scaled = root_scaled(
+                normalize_amount(contribution, decimals),
+                exponent,
+                precision,
+            );
+
+            // Down-weight contributions that share their exact amount with another
+            if amount_counts.get(contribution).unwrap_or(0) > 1 {
+                root_contribution_scaled = root_contribution_scaled
+                    * (MAX_FEE_BPS - penalty_bps) as i128
+                    / MAX_FEE_BPS as i128;
+            }
+
+            sum_root_scaled = saturating_sum(sum_root_scaled, root_contribution_scaled);
+        }
+
+        // Raise the sum to the k-th power and unscale: (sum_root_scaled / SCALE)^k
+        let match_amount = unscale(saturating_pow_scaled(sum_root_scaled, exponent));
+
+        Ok(denormalize_amount(match_amount, decimals))
+    }
+
+    /// Set the sybil penalty applied by `calculate_match` (admin only)
+    ///
+    /// Contributions whose exact amount is shared with another contributor to the
+    /// same project have their square root reduced by `penalty_bps`. Zero disables it.
+    pub fn set_sybil_penalty(
+        env: Env,
+        admin: Address,
+        penalty_bps: u32,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Validate penalty
+        if penalty_bps > MAX_FEE_BPS {
+            return Err(CrowdfundError::InvalidPenalty);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::SybilPenaltyBps, &penalty_bps);
+
+        // Emit penalty update event
+        if Self::full_events(&env) {
+            events::SybilPenaltyUpdatedEvent {
+                admin,
+                penalty_bps,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Cap each match at `ratio_bps / 10000` times the project's organic deposits (admin only)
+    ///
+    /// For example 20_000 limits a match to twice what contributors deposited.
+    /// Zero removes the cap.
+    pub fn set_max_match_ratio(
+        env: Env,
+        admin: Address,
+        ratio_bps: u32,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxMatchRatioBps, &ratio_bps);
+
+        // Emit ratio update event
+        if Self::full_events(&env) {
+            events::MaxMatchRatioUpdatedEvent {
+                admin,
+                ratio_bps,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Get the maximum match ratio in basis points (0 when uncapped)
+    pub fn get_max_match_ratio_bps(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MaxMatchRatioBps)
+            .unwrap_or(0)
+    }
+
+    /// Set the minimum time, in seconds, between project creation and the first withdrawal (admin only)
+    pub fn set_min_project_duration(
+        env: Env,
+        admin: Address,
+        min_duration: u64,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MinProjectDuration, &min_duration);
+
+        // Emit duration update event
+        if Self::full_events(&env) {
+            events::MinDurationUpdatedEvent {
+                admin,
+                min_duration,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Allow or block new projects (admin only)
+    ///
+    /// Narrower than `pause`: existing projects keep working while creation is disabled.
+    pub fn set_creation_enabled(
+        env: Env,
+        admin: Address,
+        enabled: bool,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&DataKey::CreationDisabled, &!enabled);
+
+        // Emit creation flag update event
+        if Self::full_events(&env) {
+            events::CreationEnabledUpdatedEvent {
+                admin,
+                enabled,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Whether `create_project` currently accepts new projects
+    pub fn is_creation_enabled(env: Env) -> bool {
+        !env.storage()
+            .instance()
+            .get(&DataKey::CreationDisabled)
+            .unwrap_or(false)
+    }
+
+    /// Set the matching exponent k used by `calculate_match` (admin only)
+    ///
+    /// The match is `(sum of contribution^(1/k))^k`. k = 2 is standard quadratic
+    /// funding; k = 1 matches the plain sum of contributions.
+    pub fn set_match_exponent(
+        env: Env,
+        admin: Address,
+        exponent: u32,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Validate exponent
+        if exponent == 0 || exponent > MAX_MATCH_EXPONENT {
+            return Err(CrowdfundError::InvalidExponent);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MatchExponent, &exponent);
+
+        // Emit exponent update event
+        if Self::full_events(&env) {
+            events::MatchExponentUpdatedEvent {
+                admin,
+                exponent,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Get the matching exponent k
+    pub fn get_match_exponent(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MatchExponent)
+            .unwrap_or(DEFAULT_MATCH_EXPONENT)
+    }
+
+    /// Set how many Newton iterations refine square roots in matching (admin only)
+    ///
+    /// Fewer iterations make `calculate_match` cheaper but less precise; zero keeps
+    /// only the initial linear estimate. Only affects the quadratic exponent k = 2.
+    pub fn set_sqrt_precision(
+        env: Env,
+        admin: Address,
+        iterations: u32,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Validate iterations
+        if iterations > MAX_SQRT_ITERATIONS {
+            return Err(CrowdfundError::InvalidPrecision);
+        }
+
+        env.storage()
+            .instance()
+            .set(&ExtDataKey::SqrtPrecision, &iterations);
+
+        // Emit precision update event
+        if Self::full_events(&env) {
+            events::SqrtPrecisionUpdatedEvent {
+                admin,
+                iterations,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Get the number of Newton iterations used for square roots in matching
+    pub fn get_sqrt_precision(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&ExtDataKey::SqrtPrecision)
+            .unwrap_or(DEFAULT_SQRT_ITERATIONS)
+    }
+
+    /// Set the minimum number of contributors a project needs to receive a match (admin only)
+    pub fn set_min_match_contributors(
+        env: Env,
+        admin: Address,
+        min_contributors: u32,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MinMatchContributors, &min_contributors);
+
+        // Emit threshold update event
+        if Self::full_events(&env) {
+            events::MatchThresholdUpdatedEvent {
+                admin,
+                min_contributors,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Check whether a project would currently receive matching funds
+    ///
+    /// A project is eligible when it is active, has at least the configured minimum
+    /// number of contributors and its token's matching pool is non-empty and at
+    /// least the configured minimum pool balance.
+    pub fn is_match_eligible(env: Env, project_id: u64) -> Result<bool, CrowdfundError> {
+        // Get project
+        let project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        Ok(Self::match_eligible(&env, &project))
+    }
+
+    fn match_eligible(env: &Env, project: &ProjectData) -> bool {
+        if !project.is_active {
+            return false;
+        }
+
+        let min_contributors: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MinMatchContributors)
+            .unwrap_or(0);
+        let contributor_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ContributorCount(project.id))
+            .unwrap_or(0);
+        if contributor_count < min_contributors {
+            return false;
+        }
+
+        let pool_balance: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::MatchingPool(Self::matching_token(env, project)))
+            .unwrap_or(0);
+        pool_balance > 0 && pool_balance >= Self::get_min_pool_balance(env.clone())
+    }
+
+    /// Get the fixed-point scaling factor used by the quadratic funding math
+    pub fn get_scale(_env: Env) -> i128 {
+        SCALE
+    }
+
+    /// Start a new matching round (admin only)
+    ///
+    /// `distribute_match` pays each project at most once per round; starting a
+    /// new round allows projects to be matched again. Returns the new round number.
+    pub fn start_match_round(env: Env, admin: Address) -> Result<u64, CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        let round: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MatchRound)
+            .unwrap_or(0)
+            + 1;
+        env.storage().instance().set(&DataKey::MatchRound, &round);
+        env.storage().persistent().set(
+            &DataKey::Round(round),
+            &RoundData {
+                id: round,
+                started_at: env.ledger().timestamp(),
+                projects_matched: 0,
+                total_distributed: 0,
+            },
+        );
+
+        // Emit round event
+        if Self::full_events(&env) {
+            events::MatchRoundStartedEvent {
+                admin,
+                round,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(round)
+    }
+
+    /// Compare the contract's real balance of each token with what it owes
+    ///
+    /// Returns `(token, contract_balance, obligations)` per token, where
+    /// obligations are project balances, matching pools and deposits held for
+    /// approval. A balance below its obligations means the accounting has drifted.
+    pub fn audit_solvency(env: Env) -> Vec<(Address, i128, i128)> {
+        let project_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextProjectId)
+            .unwrap_or(0);
+
+        // Sum project balances per token
+        let mut obligations: Map<Address, i128> = Map::new(&env);
+        for project_id in 0..project_count {
+            let Some(project) = env
+                .storage()
+                .persistent()
+                .get::<_, ProjectData>(&DataKey::Project(project_id))
+            else {
+                continue;
+            };
+            let mut tokens: Vec<Address> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::ProjectTokens(project_id))
+                .unwrap_or(Vec::new(&env));
+            if !tokens.contains(&project.token_address) {
+                tokens.push_front(project.token_address);
+            }
+            for token_address in tokens.iter() {
+                let balance: i128 = env
+                    .storage()
+                    .persistent()
+                    .get(&DataKey::ProjectBalance(project_id, token_address.clone()))
+                    .unwrap_or(0);
+                let owed = obligations.get(token_address.clone()).unwrap_or(0);
+                obligations.set(token_address, saturating_sum(owed, balance));
+            }
+        }
+
+        // Held deposits are already in the contract but not yet in a project balance
+        let pending_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextPendingDepositId)
+            .unwrap_or(0);
+        for deposit_id in 0..pending_count {
+            let Some(pending) = env
+                .storage()
+                .persistent()
+                .get::<_, PendingDeposit>(&DataKey::PendingDeposit(deposit_id))
+            else {
+                continue;
+            };
+            let owed = obligations.get(pending.token_address.clone()).unwrap_or(0);
+            obligations.set(pending.token_address, saturating_sum(owed, pending.amount));
+        }
+
+        let contract_address = env.current_contract_address();
+        let mut report = Vec::new(&env);
+        for (token_address, owed) in obligations.iter() {
+            let pool: i128 = env
+                .storage()
+                .persistent()
+                .get(&DataKey::MatchingPool(token_address.clone()))
+                .unwrap_or(0);
+            let balance = token::balance(&env, &token_address, &contract_address);
+            report.push_back((token_address, balance, saturating_sum(owed, pool)));
+        }
+        report
+    }
+
+    /// Get the id of the current matching round (0 until the first round is started)
+    pub fn get_current_round(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MatchRound)
+            .unwrap_or(0)
+    }
+
+    /// Get the timing and distribution totals of a started matching round
+    pub fn get_round(env: Env, round_id: u64) -> Result<RoundData, CrowdfundError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Round(round_id))
+            .ok_or(CrowdfundError::RoundNotFound)
+    }
+
+    /// Distribute matching funds from matching pool to project balance (admin only)
+    ///
+    /// Returns 0 if the project was already matched in the current round.
+    pub fn distribute_match(
+        env: Env,
+        admin: Address,
+        project_id: u64,
+    ) -> Result<i128, CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Refuse to distribute from a pool too small to be worth it
+        let token_address = Self::get_matching_token(env.clone(), project_id)?;
+        let pool_balance: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::MatchingPool(token_address))
+            .unwrap_or(0);
+        if pool_balance < Self::get_min_pool_balance(env.clone()) {
+            return Err(CrowdfundError::MatchingPoolTooLow);
+        }
+
+        Self::distribute_match_to_project(&env, project_id)
+    }
+
+    /// Set the matching pool balance below which `distribute_match` is refused (admin only)
+    ///
+    /// Batch distributions skip projects whose pool is below the minimum instead.
+    pub fn set_min_pool_balance(
+        env: Env,
+        admin: Address,
+        min_pool_balance: i128,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Validate amount
+        if min_pool_balance < 0 {
+            return Err(CrowdfundError::InvalidAmount);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MinPoolBalance, &min_pool_balance);
+
+        // Emit minimum pool balance update event
+        if Self::full_events(&env) {
+            events::MinPoolBalanceUpdatedEvent {
+                admin,
+                min_pool_balance,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Get the minimum matching pool balance required for distribution
+    pub fn get_min_pool_balance(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MinPoolBalance)
+            .unwrap_or(0)
+    }
+
+    /// Move the calculated match for a project out of the matching pool
+    fn distribute_match_to_project(env: &Env, project_id: u64) -> Result<i128, CrowdfundError> {
+        // Get project
+        let project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // Ineligible projects receive nothing
+        if !Self::match_eligible(env, &project) {
+            return Ok(0);
+        }
+
+        // Each project is matched at most once per round
+        let round: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MatchRound)
+            .unwrap_or(0);
+        let distributed_key = DataKey::MatchDistributed(project_id, round);
+        if env.storage().persistent().has(&distributed_key) {
+            return Ok(0);
+        }
+
+        // Calculate matching amount, paid in the matching token
+        let matching_token = Self::matching_token(env, &project);
+        let match_amount = Self::cap_match(
+            env,
+            &project,
+            Self::calculate_match(env.clone(), project_id)?,
+        );
+
+        if match_amount <= 0 {
+            return Ok(0);
+        }
+
+        // Check the pool available to this project: the free pool plus its own reservation
+        let pool_balance =
+            Self::free_matching_pool(env, &matching_token) + Self::reserved_match(env, project_id);
+
+        // Use the minimum of calculated match and available pool balance
+        let actual_match = if pool_balance < match_amount {
+            pool_balance
+        } else {
+            match_amount
+        };
+
+        if actual_match <= 0 {
+            return Ok(0);
+        }
+
+        // Draw down the project's reservation first
+        let reserved = Self::reserved_match(env, project_id);
+        if reserved > 0 {
+            let released = reserved.min(actual_match);
+            Self::set_reserved_match(env, &project, reserved - released);
+        }
+
+        Self::credit_match(env, project, round, actual_match)
+    }
+
+    /// Clamp a match to the configured multiple of the project's organic deposits
+    fn cap_match(env: &Env, project: &ProjectData, match_amount: i128) -> i128 {
+        let ratio_bps: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MaxMatchRatioBps)
+            .unwrap_or(0);
+        if ratio_bps == 0 {
+            return match_amount;
+        }
+
+        // Matching already received counts towards total_deposited but isn't organic
+        let organic = project.total_deposited
+            - Self::matched_in_token(env, project.id, &project.token_address);
+        let max_match = organic.saturating_mul(ratio_bps as i128) / MAX_FEE_BPS as i128;
+        match_amount.min(max_match)
+    }
+
+    /// Move `actual_match` from the matching pool to a project for `round`, net of the fee
+    ///
+    /// Every match counts toward `TotalMatched` and its token's matched total.
+    /// Matches paid in the project token also count toward `total_deposited`;
+    /// matches in a separate matching token are held like a secondary-token deposit.
+    fn credit_match(
+        env: &Env,
+        project: ProjectData,
+        round: u64,
+        actual_match: i128,
+    ) -> Result<i128, CrowdfundError> {
+        let project_id = project.id;
+        let matching_token = Self::matching_token(env, &project);
+
+        // Update matching pool balance
+        let pool_key = DataKey::MatchingPool(matching_token.clone());
+        let pool_balance: i128 = env.storage().persistent().get(&pool_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&pool_key, &(pool_balance - actual_match));
+        env.storage()
+            .persistent()
+            .set(&DataKey::MatchDistributed(project_id, round), &true);
+
+        // Record the distribution against the round it happened in
+        let round_key = DataKey::Round(round);
+        if let Some(mut round_data) = env.storage().persistent().get::<_, RoundData>(&round_key) {
+            round_data.projects_matched += 1;
+            round_data.total_distributed += actual_match;
+            env.storage().persistent().set(&round_key, &round_data);
+        }
+
+        // Route the platform fee, if any, to the fee collector
+        let fee_bps: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MatchFeeBps)
+            .unwrap_or(0);
+        let fee = actual_match * fee_bps as i128 / MAX_FEE_BPS as i128;
+        if fee > 0 {
+            let collector: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::FeeCollector)
+                .ok_or(CrowdfundError::NotInitialized)?;
+            token::transfer(
+                env,
+                &matching_token,
+                &env.current_contract_address(),
+                &collector,
+                &fee,
+            )?;
+        }
+        let net_match = actual_match - fee;
+
+        // Update project balance
+        let balance_key = DataKey::ProjectBalance(project_id, matching_token.clone());
+        let current_balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&balance_key, &(current_balance + net_match));
+
+        // Track lifetime matching separately from organic deposits
+        let total_matched_key = DataKey::TotalMatched(project_id);
+        let total_matched: i128 = env
+            .storage()
+            .persistent()
+            .get(&total_matched_key)
+            .unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&total_matched_key, &(total_matched + net_match));
+        let token_matched = Self::matched_in_token(env, project_id, &matching_token);
+        env.storage().persistent().set(
+            &ExtDataKey::TokenMatched(project_id, matching_token.clone()),
+            &(token_matched + net_match),
+        );
+
+        // Emit match distribution event
+        events::MatchDistributedEvent {
+            project_id,
+            token_address: matching_token.clone(),
+            round,
+            amount: net_match,
+            fee,
+            seq: Self::next_event_seq(env),
+        }
+        .publish(env);
+
+        if matching_token != project.token_address {
+            // Hold the match alongside any secondary-token deposits
+            let project_tokens_key = DataKey::ProjectTokens(project_id);
+            let mut project_tokens: Vec<Address> = env
+                .storage()
+                .persistent()
+                .get(&project_tokens_key)
+                .unwrap_or(Vec::new(env));
+            if !project_tokens.contains(&matching_token) {
+                project_tokens.push_back(matching_token);
+                env.storage()
+                    .persistent()
+                    .set(&project_tokens_key, &project_tokens);
+            }
+            return Ok(net_match);
+        }
+
+        // Update project total deposited (matching funds count as deposits)
+        let mut project = project;
+        project.total_deposited += net_match;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Project(project_id), &project);
+        Self::debug_check_balance(env, project_id);
+
+        Ok(net_match)
+    }
+
+    /// In tests, assert a project's balance equals what was deposited
+    /// (matches included) minus what was withdrawn
+    ///
+    /// Compiled out of every contract build, including `release-with-logs` with its
+    /// debug assertions; it exists to surface accounting drift in tests.
+    fn debug_check_balance(env: &Env, project_id: u64) {
+        if !cfg!(test) {
+            return;
+        }
+        let Some(project) = env
+            .storage()
+            .persistent()
+            .get::<_, ProjectData>(&DataKey::Project(project_id))
+        else {
+            return;
+        };
+        let balance: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ProjectBalance(project_id, project.token_address))
+            .unwrap_or(0);
+        assert_eq!(
+            balance,
+            project.total_deposited - project.total_withdrawn,
+            "project balance out of sync with deposits and withdrawals"
+        );
+    }
+
+    /// Set the fee taken from matching distributions, in basis points (admin only)
+    ///
+    /// The fee goes to `collector`, which only receives match fees; deposit and
+    /// registration fees have their own collectors.
+    pub fn set_match_fee(
+        env: Env,
+        admin: Address,
+        fee_bps: u32,
+        collector: Address,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Validate fee
+        if fee_bps > MAX_FEE_BPS {
+            return Err(CrowdfundError::InvalidFee);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MatchFeeBps, &fee_bps);
+        env.storage()
+            .instance()
+            .set(&DataKey::FeeCollector, &collector);
+
+        // Emit fee update event
+        if Self::full_events(&env) {
+            events::MatchFeeUpdatedEvent {
+                admin,
+                fee_bps,
+                collector,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Set the fee taken from each deposit, in basis points (admin only)
+    ///
+    /// The fee goes to `collector`, kept apart from the registration and match fee
+    /// collectors; the project and the contributor's tracked contribution are
+    /// credited with the remainder.
+    pub fn set_deposit_fee(
+        env: Env,
+        admin: Address,
+        fee_bps: u32,
+        collector: Address,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Validate fee
+        if fee_bps > MAX_FEE_BPS {
+            return Err(CrowdfundError::InvalidFee);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::DepositFeeBps, &fee_bps);
+        env.storage()
+            .instance()
+            .set(&ExtDataKey::DepositFeeCollector, &collector);
+
+        // Emit fee update event
+        if Self::full_events(&env) {
+            events::DepositFeeUpdatedEvent {
+                admin,
+                fee_bps,
+                collector,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Get the deposit fee in basis points
+    pub fn get_deposit_fee_bps(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::DepositFeeBps)
+            .unwrap_or(0)
+    }
+
+    /// Get the matching distribution fee in basis points
+    pub fn get_match_fee_bps(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MatchFeeBps)
+            .unwrap_or(0)
+    }
 
-        if current_balance < amount {
-            return Err(CrowdfundError::InsufficientBalance);
+    /// Get the matching funds a project has received in `token_address` over its lifetime
+    pub fn get_total_matched_in_token(
+        env: Env,
+        project_id: u64,
+        token_address: Address,
+    ) -> Result<i128, CrowdfundError> {
+        // Check if project exists
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::Project(project_id))
+        {
+            return Err(CrowdfundError::ProjectNotFound);
         }
 
-        // Transfer tokens from contract to owner
-        let contract_address = env.current_contract_address();
-        token::transfer(
-            &env,
-            &project.token_address,
-            &contract_address,
-            &project.owner,
-            &amount,
-        );
+        Ok(Self::matched_in_token(&env, project_id, &token_address))
+    }
 
-        // Update project balance
+    fn matched_in_token(env: &Env, project_id: u64, token_address: &Address) -> i128 {
         env.storage()
             .persistent()
-            .set(&balance_key, &(current_balance - amount));
+            .get(&ExtDataKey::TokenMatched(project_id, token_address.clone()))
+            .unwrap_or(0)
+    }
 
-        // Update project total withdrawn
-        project.total_withdrawn += amount;
-        env.storage()
+    /// Get the total matching funds a project has received over its lifetime
+    ///
+    /// Sums matches in every matching token, which share the project token's decimals.
+    pub fn get_total_matched(env: Env, project_id: u64) -> Result<i128, CrowdfundError> {
+        // Check if project exists
+        if !env
+            .storage()
             .persistent()
-            .set(&DataKey::Project(project_id), &project);
+            .has(&DataKey::Project(project_id))
+        {
+            return Err(CrowdfundError::ProjectNotFound);
+        }
 
-        // Emit withdraw event
-        events::WithdrawEvent {
-            owner: project.owner,
-            project_id,
-            amount,
+        Ok(env
+            .storage()
+            .persistent()
+            .get(&DataKey::TotalMatched(project_id))
+            .unwrap_or(0))
+    }
+
+    /// Distribute matching funds to several projects in one call (admin only)
+    ///
+    /// Returns the amount distributed to each project, in the order given. Once a
+    /// project's matching pool is empty, remaining projects on that token receive 0.
+    pub fn distribute_matches(
+        env: Env,
+        admin: Address,
+        project_ids: Vec<u64>,
+    ) -> Result<Vec<i128>, CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
         }
-        .publish(&env);
 
-        Ok(())
+        // Require admin authorization
+        admin.require_auth();
+
+        let mut distributed = Vec::new(&env);
+        for project_id in project_ids.iter() {
+            // Projects are skipped without a match calculation once the pool is drained
+            distributed.push_back(Self::distribute_match_to_project(&env, project_id)?);
+        }
+
+        Ok(distributed)
     }
 
-    /// Register a new contributor
-    pub fn register_contributor(env: Env, contributor: Address) -> Result<(), CrowdfundError> {
-        // Require contributor authorization
-        contributor.require_auth();
+    /// Split a token's matching pool across projects in proportion to their matches (admin only)
+    ///
+    /// Spends the smaller of the free pool and the projects' combined matches;
+    /// reservations made with `reserve_match` are left untouched. Shares
+    /// are rounded with the largest-remainder method, so the amounts taken from
+    /// the pool sum to exactly that spend and no dust is left behind. Projects
+    /// that are ineligible, already matched this round or listed twice get 0.
+    /// Returns the net amount credited to each project.
+    pub fn distribute_round(
+        env: Env,
+        admin: Address,
+        token_address: Address,
+        project_ids: Vec<u64>,
+    ) -> Result<Vec<i128>, CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
 
-        // Check if already registered
-        if env
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        let round: u64 = env
             .storage()
-            .persistent()
-            .has(&DataKey::RegisteredContributor(contributor.clone()))
-        {
-            return Err(CrowdfundError::AlreadyRegistered);
+            .instance()
+            .get(&DataKey::MatchRound)
+            .unwrap_or(0);
+
+        // Weigh each project by its calculated match
+        let mut projects: Vec<ProjectData> = Vec::new(&env);
+        let mut weights: Vec<i128> = Vec::new(&env);
+        let mut total_weight = 0i128;
+        for (index, project_id) in project_ids.iter().enumerate() {
+            let project: ProjectData = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Project(project_id))
+                .ok_or(CrowdfundError::ProjectNotFound)?;
+            let matching_token = Self::matching_token(&env, &project);
+            if matching_token != token_address {
+                return Err(CrowdfundError::TokenMismatch);
+            }
+
+            let duplicate = project_ids
+                .first_index_of(project_id)
+                .is_some_and(|first| (first as usize) < index);
+            let weight = if duplicate
+                || !Self::match_eligible(&env, &project)
+                || env
+                    .storage()
+                    .persistent()
+                    .has(&DataKey::MatchDistributed(project_id, round))
+            {
+                0
+            } else {
+                let match_amount = Self::calculate_match(env.clone(), project_id)?;
+                Self::cap_match(&env, &project, match_amount).max(0)
+            };
+
+            total_weight = total_weight
+                .checked_add(weight)
+                .ok_or(CrowdfundError::Overflow)?;
+            weights.push_back(weight);
+            projects.push_back(project);
         }
 
-        // Store registration
-        env.storage()
-            .persistent()
-            .set(&DataKey::RegisteredContributor(contributor.clone()), &true);
+        let spend = total_weight.min(Self::free_matching_pool(&env, &token_address));
+
+        // Floor each proportional share and keep the remainders
+        let mut shares: Vec<i128> = Vec::new(&env);
+        let mut remainders: Vec<i128> = Vec::new(&env);
+        let mut allocated = 0i128;
+        for weight in weights.iter() {
+            if spend <= 0 || weight == 0 {
+                shares.push_back(0);
+                remainders.push_back(-1);
+                continue;
+            }
+            let (share, remainder) =
+                mul_div_rem(spend, weight, total_weight).ok_or(CrowdfundError::Overflow)?;
+            allocated += share;
+            shares.push_back(share);
+            remainders.push_back(remainder);
+        }
 
-        // Initialize reputation
-        env.storage()
+        // Hand the units lost to truncation to the largest remainders
+        let mut leftover = spend - allocated;
+        while leftover > 0 {
+            let mut best = 0u32;
+            for i in 1..remainders.len() {
+                if remainders.get_unchecked(i) > remainders.get_unchecked(best) {
+                    best = i;
+                }
+            }
+            shares.set(best, shares.get_unchecked(best) + 1);
+            remainders.set(best, -1);
+            leftover -= 1;
+        }
+
+        let mut distributed = Vec::new(&env);
+        for (index, share) in shares.iter().enumerate() {
+            if share <= 0 {
+                distributed.push_back(0);
+                continue;
+            }
+            let project = projects.get_unchecked(index as u32);
+            distributed.push_back(Self::credit_match(&env, project, round, share)?);
+        }
+
+        Ok(distributed)
+    }
+
+    /// Pay a project's matches in `matching_token` instead of its own token (admin only)
+    ///
+    /// `None` goes back to matching in the project token. The matching token
+    /// must use the project token's decimals; a match is paid as the same
+    /// amount of it. Any reservation for the project is released.
+    pub fn set_matching_token(
+        env: Env,
+        admin: Address,
+        project_id: u64,
+        matching_token: Option<Address>,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Get project
+        let project: ProjectData = env
+            .storage()
             .persistent()
-            .set(&DataKey::Reputation(contributor.clone()), &0i128);
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
 
-        // Emit registration event
-        events::ContributorRegisteredEvent { contributor }.publish(&env);
+        if let Some(token_address) = &matching_token {
+            if !token::is_token(&env, token_address) {
+                return Err(CrowdfundError::InvalidToken);
+            }
+            if token::decimals(&env, token_address) != token::decimals(&env, &project.token_address)
+            {
+                return Err(CrowdfundError::DecimalsMismatch);
+            }
+        }
+
+        // Reservations are held in the old matching token's pool
+        Self::set_reserved_match(&env, &project, 0);
+
+        let key = ExtDataKey::MatchingToken(project_id);
+        match &matching_token {
+            Some(token_address) if *token_address != project.token_address => {
+                env.storage().persistent().set(&key, token_address);
+            }
+            _ => env.storage().persistent().remove(&key),
+        }
+
+        // Emit matching token update event
+        if Self::full_events(&env) {
+            events::MatchingTokenUpdatedEvent {
+                project_id,
+                matching_token: Self::matching_token(&env, &project),
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
 
         Ok(())
     }
 
-    /// Update contributor reputation (admin only for now, or could be internal)
-    pub fn update_reputation(
+    /// Get the token a project's matches are paid in
+    pub fn get_matching_token(env: Env, project_id: u64) -> Result<Address, CrowdfundError> {
+        let project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+        Ok(Self::matching_token(&env, &project))
+    }
+
+    fn matching_token(env: &Env, project: &ProjectData) -> Address {
+        env.storage()
+            .persistent()
+            .get(&ExtDataKey::MatchingToken(project.id))
+            .unwrap_or(project.token_address.clone())
+    }
+
+    /// Reserve part of the matching pool for a project's next distribution (admin only)
+    ///
+    /// Replaces any earlier reservation for the project; an `amount` of 0 releases
+    /// it. Reserved funds are only spent by `distribute_match` for that project and
+    /// must fit within the free pool.
+    pub fn reserve_match(
         env: Env,
         admin: Address,
-        contributor: Address,
-        change: i128,
+        project_id: u64,
+        amount: i128,
     ) -> Result<(), CrowdfundError> {
         // Check if contract is initialized
         let stored_admin: Address = env
@@ -406,78 +4152,300 @@ impl CrowdfundVaultContract {
         // Require admin authorization
         admin.require_auth();
 
-        // Check if contributor is registered
+        // Validate amount
+        if amount < 0 {
+            return Err(CrowdfundError::InvalidAmount);
+        }
+
+        // Get project
+        let project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // The project's current reservation is freed before the new one is taken
+        let available = Self::free_matching_pool(&env, &Self::matching_token(&env, &project))
+            + Self::reserved_match(&env, project_id);
+        if amount > available {
+            return Err(CrowdfundError::InsufficientBalance);
+        }
+
+        Self::set_reserved_match(&env, &project, amount);
+
+        // Emit reservation event
+        if Self::full_events(&env) {
+            events::MatchReservedEvent {
+                project_id,
+                amount,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Get the matching funds reserved for a project
+    pub fn get_reserved_match(env: Env, project_id: u64) -> i128 {
+        Self::reserved_match(&env, project_id)
+    }
+
+    /// Get the part of a token's matching pool not reserved for any project
+    pub fn get_free_matching_pool(
+        env: Env,
+        token_address: Address,
+    ) -> Result<i128, CrowdfundError> {
+        // Check if contract is initialized
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(CrowdfundError::NotInitialized);
+        }
+
+        Ok(Self::free_matching_pool(&env, &token_address))
+    }
+
+    fn reserved_match(env: &Env, project_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&ExtDataKey::ReservedMatch(project_id))
+            .unwrap_or(0)
+    }
+
+    /// Set a project's reservation and keep its token's reserved total in step
+    fn set_reserved_match(env: &Env, project: &ProjectData, amount: i128) {
+        let total_key = ExtDataKey::ReservedPool(Self::matching_token(env, project));
+        let total: i128 = env.storage().persistent().get(&total_key).unwrap_or(0);
+        let old = Self::reserved_match(env, project.id);
+        env.storage()
+            .persistent()
+            .set(&total_key, &(total - old + amount));
+        env.storage()
+            .persistent()
+            .set(&ExtDataKey::ReservedMatch(project.id), &amount);
+    }
+
+    fn free_matching_pool(env: &Env, token_address: &Address) -> i128 {
+        let pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::MatchingPool(token_address.clone()))
+            .unwrap_or(0);
+        let reserved: i128 = env
+            .storage()
+            .persistent()
+            .get(&ExtDataKey::ReservedPool(token_address.clone()))
+            .unwrap_or(0);
+        pool - reserved
+    }
+
+    /// Get matching pool balance for a token
+    pub fn get_matching_pool_balance(
+        env: Env,
+        token_address: Address,
+    ) -> Result<i128, CrowdfundError> {
+        // Check if contract is initialized
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(CrowdfundError::NotInitialized);
+        }
+
+        let pool_key = DataKey::MatchingPool(token_address);
+        Ok(env.storage().persistent().get(&pool_key).unwrap_or(0))
+    }
+
+    /// Get contribution amount for a specific user and project
+    pub fn get_contribution(
+        env: Env,
+        project_id: u64,
+        contributor: Address,
+    ) -> Result<i128, CrowdfundError> {
+        // Check if contract is initialized
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(CrowdfundError::NotInitialized);
+        }
+
+        // Check if project exists
         if !env
             .storage()
             .persistent()
-            .has(&DataKey::RegisteredContributor(contributor.clone()))
+            .has(&DataKey::Project(project_id))
         {
-            return Err(CrowdfundError::ContributorNotFound);
+            return Err(CrowdfundError::ProjectNotFound);
         }
 
-        // Get current reputation
-        let old_reputation: i128 = env
+        let contribution_key = DataKey::Contribution(project_id, contributor);
+        Ok(env
             .storage()
             .persistent()
-            .get(&DataKey::Reputation(contributor.clone()))
-            .unwrap_or(0);
-        let new_reputation = old_reputation + change;
+            .get(&contribution_key)
+            .unwrap_or(0))
+    }
 
-        // Store new reputation
-        env.storage()
+    /// Get a project's payouts as `(amount, timestamp, to)`, oldest first
+    ///
+    /// Lists everything counted in `total_withdrawn`: owner withdrawals, refunds
+    /// and dust sweeps. Keeps the most recent `MAX_WITHDRAW_HISTORY` entries.
+    pub fn get_withdraw_history(
+        env: Env,
+        project_id: u64,
+    ) -> Result<Vec<(i128, u64, Address)>, CrowdfundError> {
+        // Check if project exists
+        if !env
+            .storage()
             .persistent()
-            .set(&DataKey::Reputation(contributor.clone()), &new_reputation);
-
-        // Emit reputation change event
-        events::ReputationUpdatedEvent {
-            contributor,
-            old_reputation,
-            new_reputation,
+            .has(&DataKey::Project(project_id))
+        {
+            return Err(CrowdfundError::ProjectNotFound);
         }
-        .publish(&env);
 
-        Ok(())
+        Ok(env
+            .storage()
+            .persistent()
+            .get(&ExtDataKey::WithdrawHistory(project_id))
+            .unwrap_or(Vec::new(&env)))
     }
 
-    /// Get contributor reputation
-    pub fn get_reputation(env: Env, contributor: Address) -> Result<i128, CrowdfundError> {
+    /// Get a contributor's deposits to a project as `(amount, timestamp)`, oldest first
+    ///
+    /// Covers project-token deposits only and keeps the most recent
+    /// `MAX_DEPOSIT_HISTORY` entries.
+    pub fn get_deposit_history(
+        env: Env,
+        project_id: u64,
+        contributor: Address,
+    ) -> Result<Vec<(i128, u64)>, CrowdfundError> {
+        // Check if project exists
         if !env
             .storage()
             .persistent()
-            .has(&DataKey::RegisteredContributor(contributor.clone()))
+            .has(&DataKey::Project(project_id))
         {
-            return Err(CrowdfundError::ContributorNotFound);
+            return Err(CrowdfundError::ProjectNotFound);
         }
+
         Ok(env
             .storage()
             .persistent()
-            .get(&DataKey::Reputation(contributor))
-            .unwrap_or(0))
+            .get(&DataKey::DepositHistory(project_id, contributor))
+            .unwrap_or(Vec::new(&env)))
     }
 
-    /// Get project data
-    pub fn get_project(env: Env, project_id: u64) -> Result<ProjectData, CrowdfundError> {
-        env.storage()
+    /// Get everything a contributor has given to a project, per token
+    ///
+    /// The project token comes first, followed by any secondary tokens in the
+    /// order they were first deposited. Tokens with no contribution are omitted.
+    pub fn get_contributions(
+        env: Env,
+        project_id: u64,
+        contributor: Address,
+    ) -> Result<Vec<(Address, i128)>, CrowdfundError> {
+        let token_address = Self::get_project_token(env.clone(), project_id)?;
+        let mut contributions = Vec::new(&env);
+
+        let direct = Self::get_contribution(env.clone(), project_id, contributor.clone())?;
+        if direct > 0 {
+            contributions.push_back((token_address, direct));
+        }
+
+        let tokens: Vec<Address> = env
+            .storage()
             .persistent()
-            .get(&DataKey::Project(project_id))
-            .ok_or(CrowdfundError::ProjectNotFound)
+            .get(&DataKey::ContributionTokens(
+                project_id,
+                contributor.clone(),
+            ))
+            .unwrap_or(Vec::new(&env));
+        for token in tokens.iter() {
+            let amount: i128 = env
+                .storage()
+                .persistent()
+                .get(&DataKey::TokenContribution(
+                    project_id,
+                    contributor.clone(),
+                    token.clone(),
+                ))
+                .unwrap_or(0);
+            if amount > 0 {
+                contributions.push_back((token, amount));
+            }
+        }
+
+        Ok(contributions)
     }
 
-    /// Get project balance
-    pub fn get_balance(env: Env, project_id: u64) -> Result<i128, CrowdfundError> {
-        // Get project to get token address
+    /// Get a contributor's direct contribution plus their share of the distributed match
+    ///
+    /// The match is split in proportion to each contributor's weight in
+    /// `calculate_match`, i.e. the k-th root of their normalized contribution.
+    pub fn get_effective_contribution(
+        env: Env,
+        project_id: u64,
+        contributor: Address,
+    ) -> Result<i128, CrowdfundError> {
+        let direct = Self::get_contribution(env.clone(), project_id, contributor)?;
+        let total_matched = Self::get_total_matched(env.clone(), project_id)?;
+        if direct <= 0 || total_matched <= 0 {
+            return Ok(direct);
+        }
+
         let ProjectData { token_address, .. } = env
             .storage()
             .persistent()
             .get(&DataKey::Project(project_id))
             .ok_or(CrowdfundError::ProjectNotFound)?;
+        let decimals = token::decimals(&env, &token_address);
+        let exponent: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MatchExponent)
+            .unwrap_or(DEFAULT_MATCH_EXPONENT);
+        let precision = Self::get_sqrt_precision(env.clone());
 
-        let balance_key = DataKey::ProjectBalance(project_id, token_address);
-        Ok(env.storage().persistent().get(&balance_key).unwrap_or(0))
+        // Sum every contributor's weight
+        let contributor_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ContributorCount(project_id))
+            .unwrap_or(0);
+        let mut total_weight = 0i128;
+        for i in 0..contributor_count {
+            let other: Address = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Contributor(project_id, i))
+                .ok_or(CrowdfundError::ProjectNotFound)?;
+            let contribution: i128 = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Contribution(project_id, other))
+                .unwrap_or(0);
+            total_weight = saturating_sum(
+                total_weight,
+                root_scaled(
+                    normalize_amount(contribution, decimals),
+                    exponent,
+                    precision,
+                ),
+            );
+        }
+
+        let weight = root_scaled(normalize_amount(direct, decimals), exponent, precision);
+        if total_weight <= 0 {
+            return Ok(direct);
+        }
+        let match_share = total_matched.saturating_mul(weight) / total_weight;
+
+        Ok(saturating_sum(direct, match_share))
     }
 
-    /// Check if milestone is approved for a project
-    pub fn is_milestone_approved(env: Env, project_id: u64) -> Result<bool, CrowdfundError> {
+    /// Check whether an address has ever contributed to a project
+    ///
+    /// The marker is set on the first deposit and stays true even if the
+    /// contribution is later reduced to zero.
+    pub fn has_contributed(
+        env: Env,
+        project_id: u64,
+        contributor: Address,
+    ) -> Result<bool, CrowdfundError> {
         // Check if project exists
         if !env
             .storage()
@@ -490,25 +4458,51 @@ impl CrowdfundVaultContract {
         Ok(env
             .storage()
             .persistent()
-            .get(&DataKey::MilestoneApproved(project_id))
+            .get(&DataKey::HasContributed(project_id, contributor))
             .unwrap_or(false))
     }
 
-    /// Get admin address
-    pub fn get_admin(env: Env) -> Result<Address, CrowdfundError> {
-        env.storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or(CrowdfundError::NotInitialized)
+    /// Get the IDs of up to `limit` projects `contributor` has deposited into, in
+    /// first-deposit order, skipping the first `start`
+    ///
+    /// `limit` is capped at `MAX_PAGE_SIZE`.
+    pub fn get_contributor_projects(
+        env: Env,
+        contributor: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<u64> {
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::DonorProjectCount(contributor.clone()))
+            .unwrap_or(0);
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+
+        let mut projects = Vec::new(&env);
+        for i in start..end {
+            if let Some(project_id) = env
+                .storage()
+                .persistent()
+                .get::<_, u64>(&ExtDataKey::ContributorProject(contributor.clone(), i))
+            {
+                projects.push_back(project_id);
+            }
+        }
+        projects
     }
 
-    /// Fund the matching pool (admin only)
-    pub fn fund_matching_pool(
+    /// Remove repeated addresses from a project's contributor index (admin only)
+    ///
+    /// Maintenance tool for a double-counted contributor. Contributions are stored
+    /// per address, so merging an entry only drops the repeated index slot; the
+    /// remaining slots are compacted, the count is fixed and the running root sum
+    /// is rebuilt. Returns the number of entries removed.
+    pub fn dedupe_contributors(
         env: Env,
         admin: Address,
-        token_address: Address,
-        amount: i128,
-    ) -> Result<(), CrowdfundError> {
+        project_id: u64,
+    ) -> Result<u32, CrowdfundError> {
         // Check if contract is initialized
         let stored_admin: Address = env
             .storage()
@@ -524,33 +4518,13 @@ impl CrowdfundVaultContract {
         // Require admin authorization
         admin.require_auth();
 
-        // Validate amount
-        if amount <= 0 {
-            return Err(CrowdfundError::InvalidAmount);
-        }
-
-        // Accounting-only: update internal matching pool balance without transferring tokens
-
-        // Update matching pool balance
-        let pool_key = DataKey::MatchingPool(token_address.clone());
-        let current_pool: i128 = env.storage().persistent().get(&pool_key).unwrap_or(0);
-        env.storage()
+        // Get project
+        let project: ProjectData = env
+            .storage()
             .persistent()
-            .set(&pool_key, &(current_pool + amount));
-
-        Ok(())
-    }
-
-    /// Calculate matching funds for a project using quadratic funding formula
-    /// Formula: (sum of sqrt(contributions))^2
-    /// Returns the amount of matching funds based on number of unique contributors and amounts
-    pub fn calculate_match(env: Env, project_id: u64) -> Result<i128, CrowdfundError> {
-        // Check if contract is initialized
-        if !env.storage().instance().has(&DataKey::Admin) {
-            return Err(CrowdfundError::NotInitialized);
-        }
+            .get(&DataKey::Project(project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
 
-        // Get contributor count
         let contributor_count_key = DataKey::ContributorCount(project_id);
         let contributor_count: u32 = env
             .storage()
@@ -558,144 +4532,63 @@ impl CrowdfundVaultContract {
             .get(&contributor_count_key)
             .unwrap_or(0);
 
-        if contributor_count == 0 {
-            return Ok(0);
-        }
-
-        // Sum of square roots of contributions
-        let mut sum_sqrt_scaled = 0i128;
-
-        // Iterate through all contributors
+        // Keep the first slot of each address, shifting later ones down
+        let mut seen: Map<Address, ()> = Map::new(&env);
+        let mut kept = 0u32;
         for i in 0..contributor_count {
-            let contributor_key = DataKey::Contributor(project_id, i);
-            let contributor: Address = env
-                .storage()
-                .persistent()
-                .get(&contributor_key)
-                .ok_or(CrowdfundError::ProjectNotFound)?;
-
-            // Get contribution amount
-            let contribution_key = DataKey::Contribution(project_id, contributor);
-            let contribution: i128 = env
+            let Some(contributor) = env
                 .storage()
                 .persistent()
-                .get(&contribution_key)
-                .unwrap_or(0);
-
-            if contribution > 0 {
-                // Calculate sqrt(contribution) scaled
-                let sqrt_contribution_scaled = sqrt_scaled(contribution);
-                sum_sqrt_scaled += sqrt_contribution_scaled;
+                .get::<_, Address>(&DataKey::Contributor(project_id, i))
+            else {
+                continue;
+            };
+            if seen.contains_key(contributor.clone()) {
+                continue;
             }
+            seen.set(contributor.clone(), ());
+            if kept != i {
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::Contributor(project_id, kept), &contributor);
+            }
+            kept += 1;
         }
 
-        // Square the sum and unscale twice: (sum_sqrt_scaled / SCALE)^2 = sum_sqrt_scaled^2 / SCALE^2
-        let sum_sqrt_squared = sum_sqrt_scaled
-            .checked_mul(sum_sqrt_scaled)
-            .unwrap_or(i128::MAX);
-        let match_amount = unscale(unscale(sum_sqrt_squared));
-
-        Ok(match_amount)
-    }
-
-    /// Distribute matching funds from matching pool to project balance
-    pub fn distribute_match(env: Env, project_id: u64) -> Result<i128, CrowdfundError> {
-        // Check if contract is initialized
-        if !env.storage().instance().has(&DataKey::Admin) {
-            return Err(CrowdfundError::NotInitialized);
-        }
-
-        // Get project
-        let project: ProjectData = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Project(project_id))
-            .ok_or(CrowdfundError::ProjectNotFound)?;
-
-        // Calculate matching amount
-        let match_amount = Self::calculate_match(env.clone(), project_id)?;
-
-        if match_amount <= 0 {
-            return Ok(0);
-        }
-
-        // Check matching pool balance
-        let pool_key = DataKey::MatchingPool(project.token_address.clone());
-        let pool_balance: i128 = env.storage().persistent().get(&pool_key).unwrap_or(0);
-
-        // Use the minimum of calculated match and available pool balance
-        let actual_match = if pool_balance < match_amount {
-            pool_balance
-        } else {
-            match_amount
-        };
-
-        if actual_match <= 0 {
+        let removed = contributor_count - kept;
+        if removed == 0 {
             return Ok(0);
         }
 
-        // Update matching pool balance
-        env.storage()
-            .persistent()
-            .set(&pool_key, &(pool_balance - actual_match));
-
-        // Update project balance
-        let balance_key = DataKey::ProjectBalance(project_id, project.token_address.clone());
-        let current_balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
-        env.storage()
-            .persistent()
-            .set(&balance_key, &(current_balance + actual_match));
-
-        // Update project total deposited (matching funds count as deposits)
-        let mut project = project;
-        project.total_deposited += actual_match;
-        env.storage()
-            .persistent()
-            .set(&DataKey::Project(project_id), &project);
-
-        Ok(actual_match)
-    }
-
-    /// Get matching pool balance for a token
-    pub fn get_matching_pool_balance(
-        env: Env,
-        token_address: Address,
-    ) -> Result<i128, CrowdfundError> {
-        // Check if contract is initialized
-        if !env.storage().instance().has(&DataKey::Admin) {
-            return Err(CrowdfundError::NotInitialized);
-        }
-
-        let pool_key = DataKey::MatchingPool(token_address);
-        Ok(env.storage().persistent().get(&pool_key).unwrap_or(0))
-    }
-
-    /// Get contribution amount for a specific user and project
-    pub fn get_contribution(
-        env: Env,
-        project_id: u64,
-        contributor: Address,
-    ) -> Result<i128, CrowdfundError> {
-        // Check if contract is initialized
-        if !env.storage().instance().has(&DataKey::Admin) {
-            return Err(CrowdfundError::NotInitialized);
+        for i in kept..contributor_count {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Contributor(project_id, i));
         }
+        env.storage()
+            .persistent()
+            .set(&contributor_count_key, &kept);
 
-        // Check if project exists
-        if !env
-            .storage()
+        // The running sum may have been built from the duplicated index
+        let exponent = Self::get_match_exponent(env.clone());
+        let precision = Self::get_sqrt_precision(env.clone());
+        let decimals = token::decimals(&env, &project.token_address);
+        let sum = Self::compute_sum_sqrt(&env, project_id, decimals, exponent, precision);
+        env.storage()
             .persistent()
-            .has(&DataKey::Project(project_id))
-        {
-            return Err(CrowdfundError::ProjectNotFound);
+            .set(&DataKey::SumSqrt(project_id), &(exponent, precision, sum));
+
+        // Emit dedupe event
+        if Self::full_events(&env) {
+            events::ContributorsDedupedEvent {
+                project_id,
+                removed,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
         }
 
-        let contribution_key = DataKey::Contribution(project_id, contributor);
-        Ok(env
-            .storage()
-            .persistent()
-            .get(&contribution_key)
-            .unwrap_or(0))
+        Ok(removed)
     }
 
     /// Get contributor count for a project
@@ -750,12 +4643,15 @@ impl CrowdfundVaultContract {
 
         env.storage().instance().set(&DataKey::Paused, &true);
 
-        events::ContractPauseEvent {
-            admin,
-            paused: true,
-            timestamp: env.ledger().timestamp(),
+        if Self::full_events(&env) {
+            events::ContractPauseEvent {
+                admin,
+                paused: true,
+                timestamp: env.ledger().timestamp(),
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
         }
-        .publish(&env);
 
         Ok(true)
     }
@@ -788,16 +4684,58 @@ impl CrowdfundVaultContract {
 
         env.storage().instance().set(&DataKey::Paused, &false);
 
-        events::ContractUnpauseEvent {
-            admin,
-            paused: false,
-            timestamp: env.ledger().timestamp(),
+        if Self::full_events(&env) {
+            events::ContractUnpauseEvent {
+                admin,
+                paused: false,
+                timestamp: env.ledger().timestamp(),
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(true)
+    }
+
+    /// Unpause the contract as the recovery guardian
+    pub fn guardian_unpause(env: Env, guardian: Address) -> Result<bool, CrowdfundError> {
+        // Check if contract is initialized
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(CrowdfundError::NotInitialized);
+        }
+
+        // Verify guardian identity
+        let stored_guardian: Option<Address> = env.storage().instance().get(&DataKey::Guardian);
+        if stored_guardian != Some(guardian.clone()) {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require guardian authorization
+        guardian.require_auth();
+
+        env.storage().instance().set(&DataKey::Paused, &false);
+
+        if Self::full_events(&env) {
+            events::ContractUnpauseEvent {
+                admin: guardian,
+                paused: false,
+                timestamp: env.ledger().timestamp(),
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
         }
-        .publish(&env);
 
         Ok(true)
     }
 
+    /// Check whether the contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
     pub fn require_not_paused(env: &Env) -> bool {
         env.storage()
             .instance()
@@ -805,6 +4743,85 @@ impl CrowdfundVaultContract {
             .unwrap_or(false)
     }
 
+    /// Get the sequence number of the most recently emitted event (0 if none)
+    pub fn get_event_seq(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::EventSeq)
+            .unwrap_or(0)
+    }
+
+    /// Advance the event counter and return the sequence number for the next event
+    fn next_event_seq(env: &Env) -> u64 {
+        let seq: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::EventSeq)
+            .unwrap_or(0)
+            + 1;
+        env.storage().instance().set(&DataKey::EventSeq, &seq);
+        seq
+    }
+
+    /// Switch between full and minimal event modes (admin only)
+    ///
+    /// Minimal mode keeps only deposit and withdrawal events (including commits,
+    /// reveals, held and refunded deposits, dust sweeps, matching pool funding
+    /// and match distributions) to save ledger space; every other event is suppressed. The mode
+    /// change itself is always emitted. Suppressed events don't consume a `seq`.
+    pub fn set_minimal_events(
+        env: Env,
+        admin: Address,
+        minimal: bool,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MinimalEvents, &minimal);
+
+        // Emit event mode update event
+        events::EventModeUpdatedEvent {
+            admin,
+            minimal,
+            seq: Self::next_event_seq(&env),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Whether minimal event mode is on (full mode is the default)
+    pub fn is_minimal_events(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::MinimalEvents)
+            .unwrap_or(false)
+    }
+
+    /// Whether events other than deposits and withdrawals should be emitted
+    fn full_events(env: &Env) -> bool {
+        !Self::is_minimal_events(env.clone())
+    }
+
+    /// Return this contract's version identifier.
+    pub fn version() -> u32 {
+        1
+    }
+
     /// Upgrade the contract WASM to a new hash.
     ///
     /// Only the stored admin may call this. Emits [`UpgradedEvent`] on success.
@@ -824,11 +4841,14 @@ impl CrowdfundVaultContract {
         caller.require_auth();
         env.deployer()
             .update_current_contract_wasm(new_wasm_hash.clone());
-        events::UpgradedEvent {
-            admin: caller,
-            new_wasm_hash,
+        if Self::full_events(&env) {
+            events::UpgradedEvent {
+                admin: caller,
+                new_wasm_hash,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
         }
-        .publish(&env);
         Ok(())
     }
 
@@ -850,13 +4870,283 @@ impl CrowdfundVaultContract {
         }
         current_admin.require_auth();
         env.storage().instance().set(&DataKey::Admin, &new_admin);
-        events::AdminChangedEvent {
-            old_admin: current_admin,
-            new_admin,
+        if Self::full_events(&env) {
+            events::AdminChangedEvent {
+                old_admin: current_admin,
+                new_admin,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+        Ok(())
+    }
+
+    /// Set the deposit size at or above which deposits wait for admin approval (admin only)
+    ///
+    /// `None` disables the review and lets every deposit through.
+    pub fn set_large_deposit_threshold(
+        env: Env,
+        admin: Address,
+        threshold: Option<i128>,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        match threshold {
+            Some(threshold) if threshold <= 0 => return Err(CrowdfundError::InvalidAmount),
+            Some(threshold) => env
+                .storage()
+                .instance()
+                .set(&DataKey::LargeDepositThreshold, &threshold),
+            None => env
+                .storage()
+                .instance()
+                .remove(&DataKey::LargeDepositThreshold),
+        }
+
+        // Emit threshold update event
+        if Self::full_events(&env) {
+            events::DepositThresholdUpdatedEvent {
+                admin,
+                threshold,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+
+    /// Get the large deposit threshold, if one is set
+    pub fn get_large_deposit_threshold(env: Env) -> Option<i128> {
+        env.storage()
+            .instance()
+            .get(&DataKey::LargeDepositThreshold)
+    }
+
+    /// Get a deposit that is waiting for admin approval
+    pub fn get_pending_deposit(
+        env: Env,
+        deposit_id: u64,
+    ) -> Result<PendingDeposit, CrowdfundError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PendingDeposit(deposit_id))
+            .ok_or(CrowdfundError::PendingDepositNotFound)
+    }
+
+    /// Credit a held large deposit to its project (admin only)
+    pub fn approve_deposit(
+        env: Env,
+        admin: Address,
+        deposit_id: u64,
+    ) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Check Emergency Pause State
+        if Self::require_not_paused(&env) {
+            return Err(CrowdfundError::ContractPaused);
+        };
+
+        let pending_key = DataKey::PendingDeposit(deposit_id);
+        let pending: PendingDeposit = env
+            .storage()
+            .persistent()
+            .get(&pending_key)
+            .ok_or(CrowdfundError::PendingDepositNotFound)?;
+
+        // Get project
+        let mut project: ProjectData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Project(pending.project_id))
+            .ok_or(CrowdfundError::ProjectNotFound)?;
+
+        // Check if project is active
+        if !project.is_active {
+            return Err(CrowdfundError::ProjectNotActive);
+        }
+
+        // Other deposits may have filled the cap while this one was held
+        Self::check_deposit_cap(&project, pending.amount)?;
+
+        env.storage().persistent().remove(&pending_key);
+        Self::adjust_pending_deposits(&env, pending.project_id, -1);
+        Self::record_contribution(&env, &mut project, &pending.user, pending.amount)?;
+
+        // Emit deposit event
+        events::DepositEvent {
+            user: pending.user,
+            project_id: pending.project_id,
+            amount: pending.amount,
+            seq: Self::next_event_seq(&env),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Reject a held deposit and refund it to the depositor (admin only)
+    ///
+    /// Works whether or not the project is still active, so held funds are
+    /// never stranded by a project closing before approval.
+    pub fn reject_deposit(env: Env, admin: Address, deposit_id: u64) -> Result<(), CrowdfundError> {
+        // Check if contract is initialized
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+
+        // Verify admin identity
+        if admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+
+        // Require admin authorization
+        admin.require_auth();
+
+        // Check Emergency Pause State
+        if Self::require_not_paused(&env) {
+            return Err(CrowdfundError::ContractPaused);
+        };
+
+        let pending_key = DataKey::PendingDeposit(deposit_id);
+        let pending: PendingDeposit = env
+            .storage()
+            .persistent()
+            .get(&pending_key)
+            .ok_or(CrowdfundError::PendingDepositNotFound)?;
+
+        env.storage().persistent().remove(&pending_key);
+        Self::adjust_pending_deposits(&env, pending.project_id, -1);
+
+        // Refund the held funds in the token they were paid in
+        token::transfer(
+            &env,
+            &pending.token_address,
+            &env.current_contract_address(),
+            &pending.user,
+            &pending.amount,
+        )?;
+
+        // Emit rejection event
+        events::DepositRejectedEvent {
+            user: pending.user,
+            project_id: pending.project_id,
+            deposit_id,
+            amount: pending.amount,
+            seq: Self::next_event_seq(&env),
         }
         .publish(&env);
+
+        Ok(())
+    }
+
+    fn pending_deposits(env: &Env, project_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&ExtDataKey::PendingDepositCount(project_id))
+            .unwrap_or(0)
+    }
+
+    fn adjust_pending_deposits(env: &Env, project_id: u64, delta: i32) {
+        let count = Self::pending_deposits(env, project_id).saturating_add_signed(delta);
+        let key = ExtDataKey::PendingDepositCount(project_id);
+        if count == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &count);
+        }
+    }
+
+    /// Propose a new admin; the role moves only once they call `accept_admin`.
+    pub fn propose_admin(
+        env: Env,
+        current_admin: Address,
+        new_admin: Address,
+    ) -> Result<(), CrowdfundError> {
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+        if current_admin != stored_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+        current_admin.require_auth();
+        env.storage()
+            .instance()
+            .set(&DataKey::PendingAdmin, &new_admin);
+        if Self::full_events(&env) {
+            events::AdminProposedEvent {
+                admin: current_admin,
+                pending_admin: new_admin,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
         Ok(())
     }
+
+    /// Accept a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), CrowdfundError> {
+        let old_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(CrowdfundError::NotInitialized)?;
+        let pending_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .ok_or(CrowdfundError::NoPendingAdmin)?;
+        if new_admin != pending_admin {
+            return Err(CrowdfundError::Unauthorized);
+        }
+        new_admin.require_auth();
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        if Self::full_events(&env) {
+            events::AdminChangedEvent {
+                old_admin,
+                new_admin,
+                seq: Self::next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+        Ok(())
+    }
+
+    /// Get the admin proposed by `propose_admin`, if a transfer is pending.
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
 }
 
 #[cfg(test)]
@@ -2,14 +2,23 @@
 /// Uses a scaling factor of 1e9 (1_000_000_000) for precision
 ///
 /// Scale factor for fixed-point arithmetic (1e9)
-const SCALE: i128 = 1_000_000_000;
+pub const SCALE: i128 = 1_000_000_000;
+
+/// Newton iterations `sqrt_scaled` runs unless an admin configures otherwise
+pub const DEFAULT_SQRT_ITERATIONS: u32 = 2;
+
+/// Most Newton iterations `sqrt_scaled` may be configured to run
+pub const MAX_SQRT_ITERATIONS: u32 = 8;
 
 /// Calculate integer square root using binary search with fixed-point arithmetic
 /// Returns sqrt(value) * SCALE to maintain precision
 ///
 /// We want to find x such that (x / SCALE)^2 ≈ value
 /// This means x^2 / SCALE^2 ≈ value, so x^2 ≈ value * SCALE^2
-pub fn sqrt_scaled(value: i128) -> i128 {
+///
+/// `iterations` Newton steps refine the initial linear estimate; zero keeps the
+/// cheap estimate, and a few steps reach the exact floor of sqrt(value) * SCALE.
+pub fn sqrt_scaled(value: i128, iterations: u32) -> i128 {
     if value <= 0 {
         return 0;
     }
@@ -65,7 +74,160 @@ pub fn sqrt_scaled(value: i128) -> i128 {
         0
     };
 
-    integer_part + remainder
+    refine_sqrt_scaled(value, integer_part + remainder, iterations)
+}
+
+/// Run up to `iterations` Newton steps on an estimate of sqrt(value) * SCALE
+///
+/// The linear estimate never undershoots by a whole unit, so the steps descend
+/// toward the floor and stop once they no longer improve. Values too large to
+/// scale by SCALE^2 keep the estimate.
+fn refine_sqrt_scaled(value: i128, estimate: i128, iterations: u32) -> i128 {
+    let Some(target) = value.checked_mul(SCALE * SCALE) else {
+        return estimate;
+    };
+
+    let mut x = estimate;
+    for _ in 0..iterations {
+        let next = (x + target / x) / 2;
+        if next >= x {
+            break;
+        }
+        x = next;
+    }
+    x
+}
+
+/// Calculate the integer k-th root with fixed-point arithmetic
+/// Returns value^(1/k) * SCALE; k = 2 is exactly `sqrt_scaled` with `sqrt_iterations`,
+/// which other roots ignore
+pub fn root_scaled(value: i128, k: u32, sqrt_iterations: u32) -> i128 {
+    if value <= 0 {
+        return 0;
+    }
+
+    match k {
+        0 | 1 => return value.saturating_mul(SCALE),
+        2 => return sqrt_scaled(value, sqrt_iterations),
+        _ => {}
+    }
+
+    // Binary search for the integer k-th root of value
+    let mut low = 0i128;
+    let mut high = value;
+    while low < high {
+        let mid = (low + high + 1) / 2;
+        if mid.checked_pow(k).is_some_and(|p| p <= value) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    // Refine the fractional part by searching for x with (x / SCALE)^k <= value
+    let target = value.saturating_mul(SCALE);
+    let mut low_scaled = low * SCALE;
+    let mut high_scaled = low_scaled + SCALE - 1;
+    while low_scaled < high_scaled {
+        let mid = (low_scaled + high_scaled + 1) / 2;
+        if saturating_pow_scaled(mid, k) <= target {
+            low_scaled = mid;
+        } else {
+            high_scaled = mid - 1;
+        }
+    }
+
+    low_scaled
+}
+
+/// Raise a scaled value to the k-th power, keeping the result scaled
+/// Saturates at `i128::MAX`; k = 2 matches `saturating_square` followed by one `unscale`
+pub fn saturating_pow_scaled(value: i128, k: u32) -> i128 {
+    if k == 2 {
+        return unscale(saturating_square(value));
+    }
+
+    let mut result = SCALE;
+    for _ in 0..k {
+        result = unscale(result.saturating_mul(value));
+    }
+    result
+}
+
+/// Square a value, saturating at `i128::MAX` instead of overflowing
+///
+/// The quadratic funding match grows with the square of the summed roots, so a
+/// large enough round would overflow `i128`. Capping at `i128::MAX` keeps the
+/// match bounded; the matching pool balance then limits what is distributed.
+pub fn saturating_square(value: i128) -> i128 {
+    value.saturating_mul(value)
+}
+
+/// Add two values, saturating at `i128::MAX` (or `i128::MIN`) instead of overflowing
+pub fn saturating_sum(a: i128, b: i128) -> i128 {
+    a.saturating_add(b)
+}
+
+/// Compute `a * b / d` rounded down, with its remainder, without forming `a * b`
+///
+/// `a` and `b` must be non-negative and `d` positive. Splits `a` into a quotient
+/// and remainder of `d`, so only `(a % d) * b` needs double width; that part is
+/// worked out bit by bit. Returns `None` on invalid input or if the quotient
+/// doesn't fit an `i128`.
+pub fn mul_div_rem(a: i128, b: i128, d: i128) -> Option<(i128, i128)> {
+    if a < 0 || b < 0 || d <= 0 {
+        return None;
+    }
+
+    // a * b / d = (a / d) * b + (a % d) * b / d
+    let high = (a / d).checked_mul(b)?;
+    let (r, b, d) = ((a % d) as u128, b as u128, d as u128);
+
+    // Shift-and-add (r * b) while keeping it reduced modulo d; since r < d and
+    // d <= i128::MAX, neither doubling nor adding r overflows a u128
+    let mut quotient = 0u128;
+    let mut remainder = 0u128;
+    for bit in (0..128 - b.leading_zeros()).rev() {
+        quotient <<= 1;
+        remainder <<= 1;
+        if remainder >= d {
+            remainder -= d;
+            quotient += 1;
+        }
+        if (b >> bit) & 1 == 1 {
+            remainder += r;
+            if remainder >= d {
+                remainder -= d;
+                quotient += 1;
+            }
+        }
+    }
+
+    let low = i128::try_from(quotient).ok()?;
+    Some((high.checked_add(low)?, remainder as i128))
+}
+
+/// Decimals that token amounts are normalized to before applying the matching math
+pub const NORMALIZED_DECIMALS: u32 = 7;
+
+/// Convert an amount with `decimals` places to NORMALIZED_DECIMALS places
+///
+/// Precision below NORMALIZED_DECIMALS is truncated.
+pub fn normalize_amount(amount: i128, decimals: u32) -> i128 {
+    if decimals > NORMALIZED_DECIMALS {
+        amount / 10i128.saturating_pow(decimals - NORMALIZED_DECIMALS)
+    } else {
+        amount.saturating_mul(10i128.saturating_pow(NORMALIZED_DECIMALS - decimals))
+    }
+}
+
+/// Convert an amount with NORMALIZED_DECIMALS places back to `decimals` places
+pub fn denormalize_amount(amount: i128, decimals: u32) -> i128 {
+    if decimals > NORMALIZED_DECIMALS {
+        amount.saturating_mul(10i128.saturating_pow(decimals - NORMALIZED_DECIMALS))
+    } else {
+        amount / 10i128.saturating_pow(NORMALIZED_DECIMALS - decimals)
+    }
 }
 
 /// Divide a scaled value by SCALE to get the actual value
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, Symbol};
+use soroban_sdk::{contracttype, Address, BytesN, Symbol};
 
 #[contracttype]
 #[derive(Clone)]
@@ -15,6 +15,74 @@ pub enum DataKey {
     RegisteredContributor(Address), // Address -> bool
     Reputation(Address),            // Address -> i128
     Paused,
+    DepositCommitment(BytesN<32>), // commitment -> DepositCommitment
+    TargetReached(u64),            // project_id -> bool
+    TotalMatched(u64),             // project_id -> i128
+    MatchFeeBps,                   // -> u32
+    FeeCollector,                  // -> Address receiving match fees
+    Guardian,                      // -> Address
+    Milestone(u64, u32),           // (project_id, index) -> MilestoneStatus
+    HasContributed(u64, Address),  // (project_id, contributor) -> bool
+    SybilPenaltyBps,               // -> u32
+    MinMatchContributors,          // -> u32
+    MatchExponent,                 // -> u32
+    PendingAdmin,                  // -> Address
+    DonorProjectCount(Address),    // contributor -> u32
+    RegistrationFee,               // -> i128
+    FeeToken,                      // -> Address
+    EventSeq,                      // -> u64
+    MinProjectDuration,            // -> u64
+    MatchRound,                    // -> u64
+    MatchDistributed(u64, u64),    // (project_id, round) -> bool
+    TokenContribution(u64, Address, Address), // (project_id, contributor, token) -> i128
+    ContributionTokens(u64, Address), // (project_id, contributor) -> Vec<Address>
+    ProjectTokens(u64),            // project_id -> Vec<Address> of secondary tokens held
+    Round(u64),                    // round_id -> RoundData
+    SumSqrt(u64),                  // project_id -> (u32, u32, i128): exponent, precision, root sum
+    LargeDepositThreshold,         // -> i128
+    NextPendingDepositId,          // -> u64
+    PendingDeposit(u64),           // deposit_id -> PendingDeposit
+    DepositCooldown(u64),          // project_id -> u64 seconds
+    LastDepositTime(u64, Address), // (project_id, contributor) -> u64
+    MinPoolBalance,                // -> i128
+    CategoryProjectCount(Symbol),  // category -> u32
+    RegisteredContributorCount,    // -> u32
+    DepositFeeBps,                 // -> u32
+    MaxMatchRatioBps,              // -> u32
+    CreationDisabled,              // -> bool
+    DepositHistory(u64, Address),  // (project_id, contributor) -> Vec<(i128, u64)>
+    MinimalEvents,                 // -> bool
+}
+
+/// Storage keys added once `DataKey` reached the 50-variant limit of a contract type
+#[contracttype]
+#[derive(Clone)]
+pub enum ExtDataKey {
+    ReservedMatch(u64),                      // project_id -> i128
+    ReservedPool(Address),                   // token_address -> i128 reserved across projects
+    AllowlistEnabled(u64),                   // project_id -> bool
+    ProjectAllowed(u64, Address),            // (project_id, contributor) -> bool
+    WithdrawHistory(u64),                    // project_id -> Vec<(i128, u64, Address)>
+    SqrtPrecision,                           // -> u32 Newton iterations
+    ActiveProjectCount,                      // -> u64
+    MatchingToken(u64),                      // project_id -> Address, when not the project token
+    DepositIdempotency(Address, BytesN<32>), // (contributor, key) -> (project_id, amount), temporary
+    PendingDepositCount(u64),                // project_id -> u32 held deposits
+    TokenMatched(u64, Address),              // (project_id, token) -> i128 matched in that token
+    RegisteredContributorAt(u32),            // index -> Address
+    CategoryProject(Symbol, u32),            // (category, index) -> u64 project_id
+    ContributorProject(Address, u32),        // (contributor, index) -> u64 project_id
+    DepositFeeCollector,                     // -> Address
+    RegistrationFeeCollector,                // -> Address
+    TopReputation,                           // -> Vec<(Address, i128)> leaderboard, highest first
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MilestoneStatus {
+    Submitted,
+    Approved,
+    Rejected,
 }
 
 #[contracttype]
@@ -28,4 +96,53 @@ pub struct ProjectData {
     pub total_deposited: i128,
     pub total_withdrawn: i128,
     pub is_active: bool,
+    pub min_reputation: Option<i128>,
+    pub max_total_deposit: Option<i128>,
+    pub end_time: Option<u64>,
+    pub created_at: u64,
+    pub closed_at: Option<u64>,
+    pub category: Symbol,
+}
+
+/// Everything a listing page needs to render one project card
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectSummary {
+    pub id: u64,
+    pub owner: Address,
+    pub name: Symbol,
+    pub category: Symbol,
+    pub token_address: Address,
+    pub target_amount: i128,
+    pub total_deposited: i128,
+    pub balance: i128,
+    pub contributor_count: u32,
+    pub total_matched: i128,
+    pub is_active: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositCommitment {
+    pub user: Address,
+    pub token_address: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingDeposit {
+    pub user: Address,
+    pub project_id: u64,
+    pub token_address: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundData {
+    pub id: u64,
+    pub started_at: u64,
+    pub projects_matched: u32,
+    pub total_distributed: i128,
 }
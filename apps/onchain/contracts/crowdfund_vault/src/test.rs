@@ -1,10 +1,10 @@
 use crate::errors::CrowdfundError;
 use crate::{CrowdfundVaultContract, CrowdfundVaultContractClient};
 use soroban_sdk::{
-    symbol_short,
-    testutils::{Address as _, Events},
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Events, Ledger},
     token::{StellarAssetClient, TokenClient},
-    Address, Env,
+    vec, Address, Bytes, BytesN, Env, Map, Symbol, TryFromVal, Val, Vec,
 };
 
 fn create_token_contract<'a>(
@@ -52,7 +52,7 @@ fn test_initialize() {
     let (client, admin, _, _, _) = setup_test(&env);
 
     // Initialize contract
-    client.initialize(&admin);
+    client.initialize(&admin, &None);
 
     // Verify admin is set
     assert_eq!(client.get_admin(), admin);
@@ -66,10 +66,10 @@ fn test_double_initialization_fails() {
     let (client, admin, _, _, _) = setup_test(&env);
 
     // Initialize contract
-    client.initialize(&admin);
+    client.initialize(&admin, &None);
 
     // Try to initialize again - should fail
-    let result = client.try_initialize(&admin);
+    let result = client.try_initialize(&admin, &None);
     assert_eq!(result, Err(Ok(CrowdfundError::AlreadyInitialized)));
 }
 
@@ -81,7 +81,7 @@ fn test_create_project() {
     let (client, admin, owner, _, token_client) = setup_test(&env);
 
     // Initialize contract
-    client.initialize(&admin);
+    client.initialize(&admin, &None);
 
     // Create project
     let project_id = client.create_project(
@@ -89,6 +89,8 @@ fn test_create_project() {
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
     assert_eq!(project_id, 0);
@@ -103,6 +105,188 @@ fn test_create_project() {
     assert!(project.is_active);
 }
 
+#[test]
+fn test_get_project_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    assert_eq!(client.get_project_token(&project_id), token_client.address);
+
+    let result = client.try_get_project_token(&99);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
+}
+
+#[test]
+fn test_get_projects_summary() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    for i in 0..5 {
+        client.create_project(
+            &owner,
+            &symbol_short!("TestProj"),
+            &(1_000 * (i + 1)),
+            &token_client.address,
+            &None,
+            &symbol_short!("General"),
+        );
+    }
+    client.deposit(&user, &2, &700, &None);
+    client.close_project(&admin, &4);
+
+    // A middle page
+    let page = client.get_projects_summary(&1, &2);
+    assert_eq!(page.len(), 2);
+    let first = page.get(0).unwrap();
+    assert_eq!(first.id, 1);
+    assert_eq!(first.target_amount, 2_000);
+    assert_eq!(first.contributor_count, 0);
+    let second = page.get(1).unwrap();
+    assert_eq!(second.id, 2);
+    assert_eq!(second.owner, owner);
+    assert_eq!(second.token_address, token_client.address);
+    assert_eq!(second.total_deposited, 700);
+    assert_eq!(second.balance, 700);
+    assert_eq!(second.contributor_count, 1);
+    assert_eq!(second.total_matched, 0);
+    assert!(second.is_active);
+
+    // The last page is short, and pages past the end are empty
+    let last = client.get_projects_summary(&3, &10);
+    assert_eq!(last.len(), 2);
+    assert!(!last.get(1).unwrap().is_active);
+    assert_eq!(client.get_projects_summary(&5, &10).len(), 0);
+    assert_eq!(client.get_projects_summary(&0, &0).len(), 0);
+}
+
+#[test]
+fn test_get_projects_by_category() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let categories = [
+        symbol_short!("Art"),
+        symbol_short!("DevTools"),
+        symbol_short!("Art"),
+        symbol_short!("DevTools"),
+        symbol_short!("Art"),
+    ];
+    for category in categories.iter() {
+        client.create_project(
+            &owner,
+            &symbol_short!("TestProj"),
+            &1_000,
+            &token_client.address,
+            &None,
+            category,
+        );
+    }
+    assert_eq!(client.get_project(&1).category, symbol_short!("DevTools"));
+
+    let art = client.get_projects_by_category(&symbol_short!("Art"), &0, &10);
+    assert_eq!(art.len(), 3);
+    assert_eq!(art.get(0).unwrap().id, 0);
+    assert_eq!(art.get(1).unwrap().id, 2);
+    assert_eq!(art.get(2).unwrap().id, 4);
+    assert!(art.iter().all(|p| p.category == symbol_short!("Art")));
+
+    // Pages are taken from within the category
+    let page = client.get_projects_by_category(&symbol_short!("Art"), &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().id, 2);
+
+    assert_eq!(
+        client
+            .get_projects_by_category(&symbol_short!("Games"), &0, &10)
+            .len(),
+        0
+    );
+}
+
+#[test]
+fn test_get_projects_summary_limit_is_bounded() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    for _ in 0..60 {
+        client.create_project(
+            &owner,
+            &symbol_short!("TestProj"),
+            &1_000,
+            &token_client.address,
+            &None,
+            &symbol_short!("General"),
+        );
+    }
+
+    let page = client.get_projects_summary(&0, &u32::MAX);
+    assert_eq!(page.len(), 50);
+    assert_eq!(page.get(49).unwrap().id, 49);
+}
+
+#[test]
+fn test_project_lifecycle_timestamps() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_700_000_000);
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    env.ledger().set_timestamp(1_700_000_500);
+    let newer_id = client.create_project(
+        &owner,
+        &symbol_short!("Newer"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    assert_eq!(client.get_project_created_at(&project_id), 1_700_000_000);
+    assert_eq!(client.get_project_created_at(&newer_id), 1_700_000_500);
+    assert_eq!(client.get_project(&project_id).closed_at, None);
+    assert_eq!(
+        client.try_get_project_created_at(&99),
+        Err(Ok(CrowdfundError::ProjectNotFound))
+    );
+
+    env.ledger().set_timestamp(1_700_003_600);
+    client.close_project(&admin, &project_id);
+    let project = client.get_project(&project_id);
+    assert_eq!(project.closed_at, Some(1_700_003_600));
+    assert_eq!(project.created_at, 1_700_000_000);
+}
+
 #[test]
 fn test_create_project_not_initialized() {
     let env = Env::default();
@@ -116,6 +300,8 @@ fn test_create_project_not_initialized() {
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
     assert_eq!(result, Err(Ok(CrowdfundError::NotInitialized)));
@@ -129,7 +315,7 @@ fn test_deposit() {
     let (client, admin, owner, user, token_client) = setup_test(&env);
 
     // Initialize contract
-    client.initialize(&admin);
+    client.initialize(&admin, &None);
 
     // Create project
     let project_id = client.create_project(
@@ -137,11 +323,13 @@ fn test_deposit() {
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
     // Deposit funds
     let deposit_amount: i128 = 500_000;
-    client.deposit(&user, &project_id, &deposit_amount);
+    client.deposit(&user, &project_id, &deposit_amount, &None);
 
     // Verify balance
     assert_eq!(client.get_balance(&project_id), deposit_amount);
@@ -152,1205 +340,5342 @@ fn test_deposit() {
 }
 
 #[test]
-fn test_deposit_invalid_amount() {
+fn test_deposit_reputation_gated() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Initialize contract
-    client.initialize(&admin);
-
-    // Create project
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &Some(50),
+        &symbol_short!("General"),
     );
 
-    // Try to deposit zero
-    let result = client.try_deposit(&user, &project_id, &0);
-    assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
+    // Unregistered users count as zero reputation
+    let result = client.try_deposit(&user, &project_id, &1_000, &None);
+    assert_eq!(result, Err(Ok(CrowdfundError::ReputationTooLow)));
+
+    // Registered but below the threshold
+    client.register_contributor(&user);
+    client.update_reputation(&admin, &user, &49);
+    let result = client.try_deposit(&user, &project_id, &1_000, &None);
+    assert_eq!(result, Err(Ok(CrowdfundError::ReputationTooLow)));
+
+    // At the threshold
+    client.update_reputation(&admin, &user, &1);
+    client.deposit(&user, &project_id, &1_000, &None);
+    assert_eq!(client.get_balance(&project_id), 1_000);
 }
 
 #[test]
-fn test_withdraw_without_approval_fails() {
+fn test_has_contributed() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Initialize contract
-    client.initialize(&admin);
-
-    // Create project
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
-    // Deposit funds
-    client.deposit(&user, &project_id, &500_000);
+    assert!(!client.has_contributed(&project_id, &user));
 
-    // Try to withdraw without milestone approval - should fail
-    let result = client.try_withdraw(&project_id, &100_000);
-    assert_eq!(result, Err(Ok(CrowdfundError::MilestoneNotApproved)));
+    client.deposit(&user, &project_id, &1_000, &None);
+    assert!(client.has_contributed(&project_id, &user));
+
+    // Further deposits keep the marker set
+    client.deposit(&user, &project_id, &1_000, &None);
+    assert!(client.has_contributed(&project_id, &user));
+
+    let result = client.try_has_contributed(&99, &user);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
 }
 
 #[test]
-fn test_withdraw_after_approval() {
+fn test_get_contributor_projects() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Initialize contract
-    client.initialize(&admin);
-
-    // Create project
-    let project_id = client.create_project(
+    let project_a = client.create_project(
         &owner,
-        &symbol_short!("TestProj"),
+        &symbol_short!("ProjA"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.create_project(
+        &owner,
+        &symbol_short!("ProjB"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let project_c = client.create_project(
+        &owner,
+        &symbol_short!("ProjC"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
-    // Deposit funds
-    let deposit_amount: i128 = 500_000;
-    client.deposit(&user, &project_id, &deposit_amount);
-
-    // Approve milestone
-    client.approve_milestone(&admin, &project_id);
-
-    // Verify milestone is approved
-    assert!(client.is_milestone_approved(&project_id));
+    assert_eq!(client.get_contributor_projects(&user, &0, &10), vec![&env]);
 
-    // Withdraw funds
-    let withdraw_amount: i128 = 200_000;
-    client.withdraw(&project_id, &withdraw_amount);
+    // Repeat deposits don't duplicate entries
+    client.deposit(&user, &project_c, &1_000, &None);
+    client.deposit(&user, &project_a, &1_000, &None);
+    client.deposit(&user, &project_c, &1_000, &None);
 
-    // Verify balance reduced
     assert_eq!(
-        client.get_balance(&project_id),
-        deposit_amount - withdraw_amount
+        client.get_contributor_projects(&user, &0, &10),
+        vec![&env, project_c, project_a]
     );
-
-    // Verify project data updated
-    let project = client.get_project(&project_id);
-    assert_eq!(project.total_withdrawn, withdraw_amount);
-
-    // Verify owner received tokens
-    assert_eq!(token_client.balance(&owner), withdraw_amount);
+    assert_eq!(
+        client.get_contributor_projects(&user, &1, &1),
+        vec![&env, project_a]
+    );
+    assert_eq!(client.get_contributor_projects(&user, &2, &10), vec![&env]);
 }
 
 #[test]
-fn test_non_admin_cannot_approve() {
+fn test_deposit_via_allowance() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, owner, _, token_client) = setup_test(&env);
-
-    // Initialize contract
-    client.initialize(&admin);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Create project
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
-    // Non-admin tries to approve milestone - should fail
-    let non_admin = Address::generate(&env);
-    let result = client.try_approve_milestone(&non_admin, &project_id);
-    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+    // Without an allowance the pull fails
+    let result = client.try_deposit_via_allowance(&user, &project_id, &250_000);
+    assert!(result.is_err());
+
+    let expiration = env.ledger().sequence() + 1_000;
+    token_client.approve(&user, &client.address, &250_000, &expiration);
+
+    client.deposit_via_allowance(&user, &project_id, &250_000);
+    assert_eq!(client.get_balance(&project_id), 250_000);
+    assert_eq!(client.get_contribution(&project_id, &user), 250_000);
+    assert_eq!(token_client.balance(&user), 10_000_000 - 250_000);
+    assert_eq!(token_client.balance(&client.address), 250_000);
+    assert_eq!(token_client.allowance(&user, &client.address), 0);
 }
 
 #[test]
-fn test_insufficient_balance_withdrawal() {
+fn test_get_contributions_multi_token() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Initialize contract
-    client.initialize(&admin);
-
-    // Create project
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
+    let (other_token, other_token_admin) = create_token_contract(&env, &admin);
+    other_token_admin.mint(&user, &50_000);
 
-    // Deposit small amount
-    client.deposit(&user, &project_id, &100_000);
+    assert_eq!(client.get_contributions(&project_id, &user), vec![&env]);
 
-    // Approve milestone
-    client.approve_milestone(&admin, &project_id);
+    client.deposit(&user, &project_id, &1_000, &None);
+    client.deposit_token(&user, &project_id, &other_token.address, &20_000);
+    client.deposit_token(&user, &project_id, &other_token.address, &5_000);
 
-    // Try to withdraw more than balance - should fail
-    let result = client.try_withdraw(&project_id, &500_000);
-    assert_eq!(result, Err(Ok(CrowdfundError::InsufficientBalance)));
+    // Depositing the project token through deposit_token is a plain deposit
+    client.deposit_token(&user, &project_id, &token_client.address, &500);
+
+    assert_eq!(
+        client.get_contributions(&project_id, &user),
+        vec![
+            &env,
+            (token_client.address.clone(), 1_500),
+            (other_token.address.clone(), 25_000)
+        ]
+    );
+
+    // Secondary tokens are held separately and don't affect the project totals
+    assert_eq!(client.get_balance(&project_id), 1_500);
+    assert_eq!(
+        client.get_balance_for_token(&project_id, &other_token.address),
+        25_000
+    );
+    assert_eq!(client.get_project(&project_id).total_deposited, 1_500);
+    assert_eq!(client.get_contribution(&project_id, &user), 1_500);
 }
 
 #[test]
-fn test_project_not_found() {
+fn test_withdraw_token() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, _, _, _) = setup_test(&env);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Initialize contract
-    client.initialize(&admin);
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let (other_token, other_token_admin) = create_token_contract(&env, &admin);
+    other_token_admin.mint(&user, &50_000);
+    client.deposit_token(&user, &project_id, &other_token.address, &20_000);
 
-    // Try to get non-existent project
-    let result = client.try_get_project(&999);
-    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
+    let result = client.try_withdraw_token(&project_id, &other_token.address, &5_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::MilestoneNotApproved)));
+
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+
+    client.withdraw_token(&project_id, &other_token.address, &5_000);
+    assert_eq!(other_token.balance(&owner), 5_000);
+    assert_eq!(
+        client.get_balance_for_token(&project_id, &other_token.address),
+        15_000
+    );
+    assert_eq!(client.get_project(&project_id).total_withdrawn, 0);
 }
 
 #[test]
-fn test_multiple_projects() {
+fn test_audit_solvency() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, owner, _, token_client) = setup_test(&env);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+    assert_eq!(client.audit_solvency(), vec![&env]);
 
-    // Initialize contract
-    client.initialize(&admin);
+    let token_admin_client = StellarAssetClient::new(&env, &token_client.address);
+    let (other_token, other_token_admin) = create_token_contract(&env, &admin);
+    other_token_admin.mint(&user, &50_000);
 
-    // Create multiple projects
-    let project_id_1 = client.create_project(
+    let project_a = client.create_project(
         &owner,
-        &symbol_short!("Project1"),
+        &symbol_short!("ProjA"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
-
-    let project_id_2 = client.create_project(
+    let project_b = client.create_project(
         &owner,
-        &symbol_short!("Project2"),
-        &2_000_000,
-        &token_client.address,
+        &symbol_short!("ProjB"),
+        &1_000_000,
+        &other_token.address,
+        &None,
+        &symbol_short!("General"),
     );
 
-    assert_eq!(project_id_1, 0);
-    assert_eq!(project_id_2, 1);
-
-    // Verify both projects exist with correct data
-    let project_1 = client.get_project(&project_id_1);
-    let project_2 = client.get_project(&project_id_2);
-
-    assert_eq!(project_1.target_amount, 1_000_000);
-    assert_eq!(project_2.target_amount, 2_000_000);
+    client.deposit(&user, &project_a, &400, &None);
+    client.deposit(&user, &project_b, &10_000, &None);
+    client.deposit_token(&user, &project_a, &other_token.address, &5_000);
+
+    // A matching pool backed by tokens actually held by the vault
+    token_admin_client.mint(&client.address, &1_000);
+    client.fund_matching_pool(&admin, &token_client.address, &1_000);
+    assert_eq!(client.distribute_match(&admin, &project_a), 400);
+
+    client.submit_milestone(&project_a, &0);
+    client.approve_milestone(&admin, &project_a, &0);
+    client.withdraw(&project_a, &300);
+    client.withdraw_token(&project_a, &other_token.address, &2_000);
+
+    // Every token's balance covers exactly what is owed
+    let report = client.audit_solvency();
+    assert_eq!(report.len(), 2);
+    assert!(report.contains((token_client.address.clone(), 1_100, 1_100)));
+    assert!(report.contains((other_token.address.clone(), 13_000, 13_000)));
+
+    // Pool funding that never arrived shows up as a shortfall
+    client.fund_matching_pool(&admin, &other_token.address, &500);
+    let report = client.audit_solvency();
+    assert!(report.contains((other_token.address.clone(), 13_000, 13_500)));
 }
 
 #[test]
-fn test_create_project_invalid_amount() {
+fn test_deposit_contribution_overflow() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, owner, _, token_client) = setup_test(&env);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
 
-    client.initialize(&admin);
+    // Exceeds the user's token balance, so only the accounting is updated
+    client.deposit(&user, &project_id, &(i128::MAX - 10), &None);
+    assert_eq!(client.get_contribution(&project_id, &user), i128::MAX - 10);
 
-    let result =
-        client.try_create_project(&owner, &symbol_short!("Test"), &0, &token_client.address);
-    assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
+    let result = client.try_deposit(&user, &project_id, &100, &None);
+    assert_eq!(result, Err(Ok(CrowdfundError::Overflow)));
+    assert_eq!(client.get_contribution(&project_id, &user), i128::MAX - 10);
 }
 
 #[test]
-fn test_deposit_project_not_found() {
+fn test_deposit_cap() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, _, user, _) = setup_test(&env);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    client.initialize(&admin);
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
 
-    let result = client.try_deposit(&user, &999, &1000);
-    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
-}
+    // The cap cannot sit below the target
+    let result = client.try_set_deposit_cap(&project_id, &Some(999_999));
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
 
-#[test]
-fn test_approve_milestone_project_not_found() {
-    let env = Env::default();
-    env.mock_all_auths();
+    client.set_deposit_cap(&project_id, &Some(1_500_000));
+    assert_eq!(
+        client.get_project(&project_id).max_total_deposit,
+        Some(1_500_000)
+    );
 
-    let (client, admin, _, _, _) = setup_test(&env);
+    // Deposits may pass the target and fill up to the cap exactly
+    client.deposit(&user, &project_id, &1_200_000, &None);
+    client.deposit(&user, &project_id, &300_000, &None);
+    assert_eq!(client.get_balance(&project_id), 1_500_000);
 
-    client.initialize(&admin);
+    let result = client.try_deposit(&user, &project_id, &1, &None);
+    assert_eq!(result, Err(Ok(CrowdfundError::DepositCapExceeded)));
 
-    let result = client.try_approve_milestone(&admin, &999);
-    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
+    // Clearing the cap lifts the ceiling
+    client.set_deposit_cap(&project_id, &None);
+    client.deposit(&user, &project_id, &1, &None);
+    assert_eq!(client.get_balance(&project_id), 1_500_001);
 }
 
 #[test]
-fn test_withdraw_project_not_found() {
+fn test_deposit_cooldown() {
     let env = Env::default();
     env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
 
-    let (client, admin, _, _, _) = setup_test(&env);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert_eq!(client.get_deposit_cooldown(&project_id), 0);
+    client.set_deposit_cooldown(&project_id, &60);
+    assert_eq!(client.get_deposit_cooldown(&project_id), 60);
 
-    client.initialize(&admin);
+    client.deposit(&user, &project_id, &100, &None);
 
-    let result = client.try_withdraw(&999, &1000);
-    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
+    // A rapid second deposit is blocked
+    env.ledger().set_timestamp(1_059);
+    let result = client.try_deposit(&user, &project_id, &100, &None);
+    assert_eq!(result, Err(Ok(CrowdfundError::DepositCooldownActive)));
+
+    // Other contributors have their own cooldown
+    let user2 = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&user2, &1_000);
+    client.deposit(&user2, &project_id, &100, &None);
+
+    // Once the cooldown has elapsed the deposit goes through
+    env.ledger().set_timestamp(1_060);
+    client.deposit(&user, &project_id, &100, &None);
+    assert_eq!(client.get_contribution(&project_id, &user), 200);
+
+    // A zero cooldown disables the check
+    client.set_deposit_cooldown(&project_id, &0);
+    client.deposit(&user, &project_id, &100, &None);
+    assert_eq!(client.get_contribution(&project_id, &user), 300);
 }
 
 #[test]
-fn test_withdraw_invalid_amount() {
+fn test_large_deposit_requires_approval() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
-
-    client.initialize(&admin);
+    client.initialize(&admin, &None);
 
     let project_id = client.create_project(
         &owner,
-        &symbol_short!("Test"),
-        &1000000,
+        &symbol_short!("TestProj"),
+        &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
-    client.deposit(&user, &project_id, &500000);
-    client.approve_milestone(&admin, &project_id);
 
-    let result = client.try_withdraw(&project_id, &0);
+    let result = client.try_set_large_deposit_threshold(&admin, &Some(0));
     assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
+    let result = client.try_set_large_deposit_threshold(&owner, &Some(10_000));
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+
+    client.set_large_deposit_threshold(&admin, &Some(10_000));
+    assert_eq!(client.get_large_deposit_threshold(), Some(10_000));
+
+    // Deposits below the threshold are credited immediately
+    client.deposit(&user, &project_id, &9_999, &None);
+    assert_eq!(client.get_balance(&project_id), 9_999);
+    assert_eq!(client.get_contribution(&project_id, &user), 9_999);
+
+    // Deposits at the threshold are held: the funds arrive but do not count yet
+    client.deposit(&user, &project_id, &10_000, &None);
+    assert_eq!(token_client.balance(&client.address), 19_999);
+    assert_eq!(client.get_balance(&project_id), 9_999);
+    assert_eq!(client.get_contribution(&project_id, &user), 9_999);
+
+    let pending = client.get_pending_deposit(&0);
+    assert_eq!(pending.user, user);
+    assert_eq!(pending.project_id, project_id);
+    assert_eq!(pending.token_address, token_client.address);
+    assert_eq!(pending.amount, 10_000);
+
+    let result = client.try_approve_deposit(&owner, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+
+    client.approve_deposit(&admin, &0);
+    assert_eq!(client.get_balance(&project_id), 19_999);
+    assert_eq!(client.get_contribution(&project_id, &user), 19_999);
+
+    // A deposit can only be approved once
+    let result = client.try_approve_deposit(&admin, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::PendingDepositNotFound)));
+    assert_eq!(
+        client.try_get_pending_deposit(&0),
+        Err(Ok(CrowdfundError::PendingDepositNotFound))
+    );
+
+    // Clearing the threshold lets large deposits through again
+    client.set_large_deposit_threshold(&admin, &None);
+    client.deposit(&user, &project_id, &50_000, &None);
+    assert_eq!(client.get_balance(&project_id), 69_999);
 }
 
 #[test]
-fn test_get_balance_project_not_found() {
+fn test_deposit_invalid_amount() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, _, _, _) = setup_test(&env);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
 
-    client.initialize(&admin);
+    // Initialize contract
+    client.initialize(&admin, &None);
 
-    let result = client.try_get_balance(&999);
-    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
-}
-
-#[test]
-fn test_is_milestone_approved_project_not_found() {
-    let env = Env::default();
-    env.mock_all_auths();
-
-    let (client, admin, _, _, _) = setup_test(&env);
-
-    client.initialize(&admin);
-
-    let result = client.try_is_milestone_approved(&999);
-    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
-}
-
-#[test]
-fn test_get_admin_not_initialized() {
-    let env = Env::default();
-    env.mock_all_auths();
-
-    let (client, _, _, _, _) = setup_test(&env);
-
-    let result = client.try_get_admin();
-    assert_eq!(result, Err(Ok(CrowdfundError::NotInitialized)));
-}
-
-// ===== Additional Tests for 90%+ Coverage =====
-
-// ===== create_project negative amount test =====
-#[test]
-fn test_create_project_negative_amount() {
-    let env = Env::default();
-    env.mock_all_auths();
-
-    let (client, admin, owner, _, token_client) = setup_test(&env);
-
-    client.initialize(&admin);
-
-    // Try to create project with negative amount
-    let result = client.try_create_project(
+    // Create project
+    let project_id = client.create_project(
         &owner,
-        &symbol_short!("Test"),
-        &-1000,
+        &symbol_short!("TestProj"),
+        &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
+
+    // Try to deposit zero
+    let result = client.try_deposit(&user, &project_id, &0, &None);
     assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
 }
 
-// ===== deposit negative amount test =====
 #[test]
-fn test_deposit_negative_amount() {
+fn test_withdraw_without_approval_fails() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
 
-    client.initialize(&admin);
+    // Initialize contract
+    client.initialize(&admin, &None);
 
+    // Create project
     let project_id = client.create_project(
         &owner,
-        &symbol_short!("Test"),
+        &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
-    // Try to deposit negative amount
-    let result = client.try_deposit(&user, &project_id, &-500);
-    assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
+    // Deposit funds
+    client.deposit(&user, &project_id, &500_000, &None);
+
+    // Try to withdraw without milestone approval - should fail
+    let result = client.try_withdraw(&project_id, &100_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::MilestoneNotApproved)));
 }
 
-// ===== deposit to inactive project test =====
 #[test]
-fn test_deposit_to_inactive_project() {
+fn test_withdraw_after_approval() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, owner, _user, token_client) = setup_test(&env);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
 
-    client.initialize(&admin);
+    // Initialize contract
+    client.initialize(&admin, &None);
 
+    // Create project
     let project_id = client.create_project(
         &owner,
-        &symbol_short!("Test"),
+        &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Deposit funds
+    let deposit_amount: i128 = 500_000;
+    client.deposit(&user, &project_id, &deposit_amount, &None);
+
+    // Approve milestone
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+
+    // Verify milestone is approved
+    assert!(client.is_milestone_approved(&project_id));
+
+    // Withdraw funds
+    let withdraw_amount: i128 = 200_000;
+    client.withdraw(&project_id, &withdraw_amount);
+
+    // Verify balance reduced
+    assert_eq!(
+        client.get_balance(&project_id),
+        deposit_amount - withdraw_amount
     );
 
-    // Get project and deactivate it (simulate project closure)
-    let mut project = client.get_project(&project_id);
-    project.is_active = false;
-    // Note: In real scenario, there would be a deactivate function
-    // For testing, we rely on the contract's own validation
+    // Verify project data updated
+    let project = client.get_project(&project_id);
+    assert_eq!(project.total_withdrawn, withdraw_amount);
+
+    // Verify owner received tokens
+    assert_eq!(token_client.balance(&owner), withdraw_amount);
 }
 
-// ===== withdraw from inactive project test =====
 #[test]
-fn test_withdraw_from_inactive_project() {
+fn test_withdraw_min_project_duration() {
     let env = Env::default();
     env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
-
-    client.initialize(&admin);
+    client.initialize(&admin, &None);
+    client.set_min_project_duration(&admin, &3_600);
 
     let project_id = client.create_project(
         &owner,
-        &symbol_short!("Test"),
+        &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
+    assert_eq!(client.get_project(&project_id).created_at, 1_000);
 
-    client.deposit(&user, &project_id, &500_000);
-    client.approve_milestone(&admin, &project_id);
+    client.deposit(&user, &project_id, &500_000, &None);
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
 
-    // Withdraw works when project is active
-    client.withdraw(&project_id, &100_000);
+    // Same block as creation
+    assert_eq!(client.get_withdrawable(&project_id), 0);
+    let result = client.try_withdraw(&project_id, &100_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::WithdrawalTooEarly)));
 
-    // Verify balance after withdrawal
-    let balance = client.get_balance(&project_id);
-    assert_eq!(balance, 400_000);
+    env.ledger().set_timestamp(4_599);
+    let result = client.try_withdraw(&project_id, &100_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::WithdrawalTooEarly)));
+
+    env.ledger().set_timestamp(4_600);
+    client.withdraw(&project_id, &100_000);
+    assert_eq!(client.get_balance(&project_id), 400_000);
 }
 
-// ===== multiple deposits to same project =====
 #[test]
-fn test_multiple_deposits() {
+fn test_withdraw_all() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
-
-    client.initialize(&admin);
+    client.initialize(&admin, &None);
 
     let project_id = client.create_project(
         &owner,
-        &symbol_short!("Test"),
+        &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
+    client.deposit(&user, &project_id, &500_000, &None);
 
-    // First deposit
-    client.deposit(&user, &project_id, &200_000);
-    assert_eq!(client.get_balance(&project_id), 200_000);
+    // Still gated on milestone approval
+    let result = client.try_withdraw_all(&project_id);
+    assert_eq!(result, Err(Ok(CrowdfundError::MilestoneNotApproved)));
 
-    // Second deposit
-    client.deposit(&user, &project_id, &300_000);
-    assert_eq!(client.get_balance(&project_id), 500_000);
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
 
-    // Verify total deposited
-    let project = client.get_project(&project_id);
-    assert_eq!(project.total_deposited, 500_000);
+    assert_eq!(client.withdraw_all(&project_id), 500_000);
+    assert_eq!(client.get_balance(&project_id), 0);
+    assert_eq!(client.get_project(&project_id).total_withdrawn, 500_000);
+    assert_eq!(token_client.balance(&owner), 500_000);
 }
 
-// ===== partial milestone withdrawal =====
 #[test]
-fn test_partial_withdrawal() {
+fn test_get_withdrawable() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
-
-    client.initialize(&admin);
+    client.initialize(&admin, &None);
 
     let project_id = client.create_project(
         &owner,
-        &symbol_short!("Test"),
+        &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
+    client.deposit(&user, &project_id, &500_000, &None);
 
-    // Deposit more than target
-    client.deposit(&user, &project_id, &1_500_000);
-    assert_eq!(client.get_balance(&project_id), 1_500_000);
+    // Nothing is withdrawable before milestone approval
+    assert_eq!(client.get_withdrawable(&project_id), 0);
 
-    client.approve_milestone(&admin, &project_id);
-
-    // Withdraw partial amount
-    client.withdraw(&project_id, &500_000);
-    assert_eq!(client.get_balance(&project_id), 1_000_000);
+    client.submit_milestone(&project_id, &0);
 
-    // Withdraw remaining
-    client.withdraw(&project_id, &1_000_000);
-    assert_eq!(client.get_balance(&project_id), 0);
+    client.approve_milestone(&admin, &project_id, &0);
+    assert_eq!(client.get_withdrawable(&project_id), 500_000);
 
-    let project = client.get_project(&project_id);
-    assert_eq!(project.total_withdrawn, 1_500_000);
+    // Nothing is withdrawable while paused
+    client.pause(&admin);
+    assert_eq!(client.get_withdrawable(&project_id), 0);
 }
 
-// ===== unauthorized owner withdrawal attempt =====
 #[test]
-fn test_unauthorized_withdrawal() {
+fn test_approve_unsubmitted_milestone_fails() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, owner, user, token_client) = setup_test(&env);
-
-    client.initialize(&admin);
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
     let project_id = client.create_project(
         &owner,
-        &symbol_short!("Test"),
+        &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
-    client.deposit(&user, &project_id, &500_000);
-    client.approve_milestone(&admin, &project_id);
-
-    // User (non-owner) tries to withdraw - should fail due to authorization
-    // The contract checks owner.require_auth() so it will panic
-    // We verify this by checking that only owner can call withdraw
+    let result = client.try_approve_milestone(&admin, &project_id, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::MilestoneNotSubmitted)));
+    assert!(!client.is_milestone_approved(&project_id));
 }
 
-// ===== milestone approval then check status =====
 #[test]
-fn test_milestone_approval_status() {
+fn test_submit_then_approve_milestone() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, _, token_client) = setup_test(&env);
-
-    client.initialize(&admin);
+    client.initialize(&admin, &None);
 
     let project_id = client.create_project(
         &owner,
-        &symbol_short!("Test"),
+        &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
-    // Before approval
-    assert!(!client.is_milestone_approved(&project_id));
+    client.submit_milestone(&project_id, &0);
 
-    // Approve milestone
-    client.approve_milestone(&admin, &project_id);
+    // A milestone can only be submitted once
+    let result = client.try_submit_milestone(&project_id, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::MilestoneAlreadySubmitted)));
 
-    // After approval
+    // Only the submitted index can be approved
+    let result = client.try_approve_milestone(&admin, &project_id, &1);
+    assert_eq!(result, Err(Ok(CrowdfundError::MilestoneNotSubmitted)));
+
+    client.approve_milestone(&admin, &project_id, &0);
     assert!(client.is_milestone_approved(&project_id));
+
+    // An approved milestone cannot be approved again
+    let result = client.try_approve_milestone(&admin, &project_id, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::MilestoneNotSubmitted)));
 }
 
-// ===== get_balance after operations =====
 #[test]
-fn test_balance_tracking() {
+fn test_reject_milestone_after_partial_withdrawal() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
-
-    client.initialize(&admin);
+    client.initialize(&admin, &None);
 
     let project_id = client.create_project(
         &owner,
-        &symbol_short!("Test"),
+        &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
+    client.deposit(&user, &project_id, &500_000, &None);
 
-    // Initial balance should be 0
-    assert_eq!(client.get_balance(&project_id), 0);
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+    client.withdraw(&project_id, &200_000);
 
-    // After deposit
-    client.deposit(&user, &project_id, &100_000);
-    assert_eq!(client.get_balance(&project_id), 100_000);
+    client.reject_milestone(&admin, &project_id, &0);
+    assert!(!client.is_milestone_approved(&project_id));
 
-    // After approval and withdrawal
-    client.approve_milestone(&admin, &project_id);
-    client.withdraw(&project_id, &50_000);
-    assert_eq!(client.get_balance(&project_id), 50_000);
+    // No further withdrawals are allowed
+    let result = client.try_withdraw(&project_id, &100_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::MilestoneNotApproved)));
+
+    // Already-withdrawn funds stay with the owner and accounting is unchanged
+    assert_eq!(token_client.balance(&owner), 200_000);
+    assert_eq!(client.get_balance(&project_id), 300_000);
+    assert_eq!(client.get_project(&project_id).total_withdrawn, 200_000);
+
+    // A rejected milestone cannot be rejected again
+    let result = client.try_reject_milestone(&admin, &project_id, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::MilestoneNotSubmitted)));
 }
 
-// ===== project data integrity after operations =====
 #[test]
-fn test_project_data_integrity() {
+fn test_milestone_index_capped() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, owner, user, token_client) = setup_test(&env);
-
-    client.initialize(&admin);
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
-        &2_000_000,
+        &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
-    // Verify initial project data
-    let project = client.get_project(&project_id);
-    assert_eq!(project.id, project_id);
-    assert_eq!(project.owner, owner);
-    assert_eq!(project.name, symbol_short!("TestProj"));
-    assert_eq!(project.target_amount, 2_000_000);
-    assert_eq!(project.total_deposited, 0);
-    assert_eq!(project.total_withdrawn, 0);
-    assert!(project.is_active);
-
-    // After deposit
-    client.deposit(&user, &project_id, &500_000);
-    let project_after_deposit = client.get_project(&project_id);
-    assert_eq!(project_after_deposit.total_deposited, 500_000);
+    // The last allowed index
+    client.submit_milestone(&project_id, &9);
 
-    // After approval and withdrawal
-    client.approve_milestone(&admin, &project_id);
-    client.withdraw(&project_id, &200_000);
-    let project_after_withdrawal = client.get_project(&project_id);
-    assert_eq!(project_after_withdrawal.total_withdrawn, 200_000);
+    let result = client.try_submit_milestone(&project_id, &10);
+    assert_eq!(result, Err(Ok(CrowdfundError::TooManyMilestones)));
 }
 
-// ===== zero target amount project =====
 #[test]
-fn test_create_project_zero_target() {
+fn test_non_admin_cannot_approve() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, _, token_client) = setup_test(&env);
 
-    client.initialize(&admin);
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    // Create project
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
 
-    let result =
-        client.try_create_project(&owner, &symbol_short!("Zero"), &0, &token_client.address);
-    assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
+    // Non-admin tries to approve milestone - should fail
+    let non_admin = Address::generate(&env);
+    let result = client.try_approve_milestone(&non_admin, &project_id, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
 }
 
-// ===== exact balance withdrawal =====
 #[test]
-fn test_withdraw_exact_balance() {
+fn test_insufficient_balance_withdrawal() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
 
-    client.initialize(&admin);
+    // Initialize contract
+    client.initialize(&admin, &None);
 
+    // Create project
     let project_id = client.create_project(
         &owner,
-        &symbol_short!("Test"),
+        &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
-    let deposit_amount = 300_000;
-    client.deposit(&user, &project_id, &deposit_amount);
-    assert_eq!(client.get_balance(&project_id), deposit_amount);
-
-    client.approve_milestone(&admin, &project_id);
+    // Deposit small amount
+    client.deposit(&user, &project_id, &100_000, &None);
 
-    // Withdraw exact balance
-    client.withdraw(&project_id, &deposit_amount);
-    assert_eq!(client.get_balance(&project_id), 0);
+    // Approve milestone
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
 
-    let project = client.get_project(&project_id);
-    assert_eq!(project.total_withdrawn, deposit_amount);
+    // Try to withdraw more than balance - should fail
+    let result = client.try_withdraw(&project_id, &500_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::InsufficientBalance)));
 }
 
-// ===== sequential project creation =====
 #[test]
-fn test_sequential_project_creation() {
+fn test_project_not_found() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, _, _, token_client) = setup_test(&env);
+    let (client, admin, _, _, _) = setup_test(&env);
 
-    client.initialize(&admin);
+    // Initialize contract
+    client.initialize(&admin, &None);
 
-    let owner1 = Address::generate(&env);
-    let owner2 = Address::generate(&env);
-    let owner3 = Address::generate(&env);
+    // Try to get non-existent project
+    let result = client.try_get_project(&999);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
+}
 
-    // Create projects sequentially
-    let id1 = client.create_project(
+#[test]
+fn test_multiple_projects() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    // Create multiple projects
+    let project_id_1 = client.create_project(
+        &owner,
+        &symbol_short!("Project1"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    let project_id_2 = client.create_project(
+        &owner,
+        &symbol_short!("Project2"),
+        &2_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    assert_eq!(project_id_1, 0);
+    assert_eq!(project_id_2, 1);
+
+    // Verify both projects exist with correct data
+    let project_1 = client.get_project(&project_id_1);
+    let project_2 = client.get_project(&project_id_2);
+
+    assert_eq!(project_1.target_amount, 1_000_000);
+    assert_eq!(project_2.target_amount, 2_000_000);
+}
+
+#[test]
+fn test_create_project_invalid_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let result = client.try_create_project(
+        &owner,
+        &symbol_short!("Test"),
+        &-1,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
+}
+
+#[test]
+fn test_deposit_project_not_found() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, user, _) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let result = client.try_deposit(&user, &999, &1000, &None);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
+}
+
+#[test]
+fn test_approve_milestone_project_not_found() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let result = client.try_approve_milestone(&admin, &999, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
+}
+
+#[test]
+fn test_withdraw_project_not_found() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let result = client.try_withdraw(&999, &1000);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
+}
+
+#[test]
+fn test_withdraw_invalid_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("Test"),
+        &1000000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &500000, &None);
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+
+    let result = client.try_withdraw(&project_id, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
+}
+
+#[test]
+fn test_get_balance_for_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &500_000, &None);
+
+    assert_eq!(
+        client.get_balance_for_token(&project_id, &token_client.address),
+        500_000
+    );
+
+    // A token the project never received has no balance
+    let (other_token, _) = create_token_contract(&env, &admin);
+    assert_eq!(
+        client.get_balance_for_token(&project_id, &other_token.address),
+        0
+    );
+
+    let result = client.try_get_balance_for_token(&99, &token_client.address);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
+}
+
+#[test]
+fn test_get_balance_project_not_found() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let result = client.try_get_balance(&999);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
+}
+
+#[test]
+fn test_is_milestone_approved_project_not_found() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let result = client.try_is_milestone_approved(&999);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
+}
+
+#[test]
+fn test_get_admin_not_initialized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _, _, _, _) = setup_test(&env);
+
+    let result = client.try_get_admin();
+    assert_eq!(result, Err(Ok(CrowdfundError::NotInitialized)));
+}
+
+// ===== Additional Tests for 90%+ Coverage =====
+
+// ===== create_project negative amount test =====
+#[test]
+fn test_create_project_negative_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    // Try to create project with negative amount
+    let result = client.try_create_project(
+        &owner,
+        &symbol_short!("Test"),
+        &-1000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
+}
+
+// ===== deposit negative amount test =====
+#[test]
+fn test_deposit_negative_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("Test"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Try to deposit negative amount
+    let result = client.try_deposit(&user, &project_id, &-500, &None);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
+}
+
+// ===== deposit to inactive project test =====
+#[test]
+fn test_deposit_to_inactive_project() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("Test"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Close the project
+    client.close_project(&admin, &project_id);
+    assert!(!client.get_project(&project_id).is_active);
+
+    let result = client.try_deposit(&user, &project_id, &1_000, &None);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotActive)));
+}
+
+#[test]
+fn test_is_refundable_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.set_deadline(&project_id, &2_000);
+    assert_eq!(client.get_project(&project_id).end_time, Some(2_000));
+    client.deposit(&user, &project_id, &500_000, &None);
+
+    // Underfunded but still running
+    assert!(!client.is_refundable(&project_id));
+
+    env.ledger().set_timestamp(2_001);
+    assert!(client.is_refundable(&project_id));
+}
+
+#[test]
+fn test_is_refundable_requires_unmet_target() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let funded = client.create_project(
+        &owner,
+        &symbol_short!("Funded"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let cancelled = client.create_project(
+        &owner,
+        &symbol_short!("Cancel"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &funded, &1_000_000, &None);
+    client.deposit(&user, &cancelled, &1_000, &None);
+
+    client.close_project(&admin, &funded);
+    client.close_project(&admin, &cancelled);
+
+    assert!(!client.is_refundable(&funded));
+    assert!(client.is_refundable(&cancelled));
+}
+
+#[test]
+fn test_get_refundable() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let underfunded = client.create_project(
+        &owner,
+        &symbol_short!("Under"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let funded = client.create_project(
+        &owner,
+        &symbol_short!("Funded"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.set_deadline(&underfunded, &2_000);
+    client.set_deadline(&funded, &2_000);
+    client.deposit(&user, &underfunded, &300_000, &None);
+    client.deposit(&user, &underfunded, &200_000, &None);
+    client.deposit(&user, &funded, &1_000_000, &None);
+
+    // Nothing is refundable while the projects are running
+    assert_eq!(client.get_refundable(&underfunded, &user), 0);
+    assert_eq!(client.get_refundable(&funded, &user), 0);
+
+    env.ledger().set_timestamp(2_001);
+    assert_eq!(client.get_refundable(&underfunded, &user), 500_000);
+    assert_eq!(client.get_refundable(&funded, &user), 0);
+
+    // Non-contributors have nothing to reclaim
+    let stranger = Address::generate(&env);
+    assert_eq!(client.get_refundable(&underfunded, &stranger), 0);
+}
+
+#[test]
+fn test_set_deadline_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    let result = client.try_set_deadline(&project_id, &1_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidDeadline)));
+
+    client.set_deadline(&project_id, &2_000);
+    let result = client.try_set_deadline(&project_id, &3_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::DeadlineAlreadySet)));
+}
+
+#[test]
+fn test_extend_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Nothing to extend until a deadline is set
+    let result = client.try_extend_deadline(&admin, &project_id, &5_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidDeadline)));
+
+    client.set_deadline(&project_id, &2_000);
+
+    client.extend_deadline(&admin, &project_id, &3_000);
+    assert_eq!(count_events(&env, "deadline_extended_event"), 1);
+    assert_eq!(client.get_project(&project_id).end_time, Some(3_000));
+
+    client.extend_deadline(&owner, &project_id, &4_000);
+    assert_eq!(client.get_project(&project_id).end_time, Some(4_000));
+
+    let result = client.try_extend_deadline(&user, &project_id, &5_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+}
+
+#[test]
+fn test_extend_deadline_rejects_backward_move() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.set_deadline(&project_id, &2_000);
+
+    let result = client.try_extend_deadline(&admin, &project_id, &1_500);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidDeadline)));
+
+    let result = client.try_extend_deadline(&admin, &project_id, &2_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidDeadline)));
+    assert_eq!(client.get_project(&project_id).end_time, Some(2_000));
+}
+
+#[test]
+fn test_sweep_dust_on_closed_project() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &500_000, &None);
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+    client.withdraw(&project_id, &499_900);
+
+    // Sweeping is only allowed once the project is closed
+    let treasury = Address::generate(&env);
+    let result = client.try_sweep_dust(&admin, &project_id, &treasury);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectStillActive)));
+
+    client.close_project(&admin, &project_id);
+
+    assert_eq!(client.sweep_dust(&admin, &project_id, &treasury), 100);
+    assert_eq!(client.get_balance(&project_id), 0);
+    assert_eq!(token_client.balance(&treasury), 100);
+}
+
+#[test]
+fn test_sweep_dust_rejects_large_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &500_000, &None);
+    client.close_project(&admin, &project_id);
+
+    let treasury = Address::generate(&env);
+    let result = client.try_sweep_dust(&admin, &project_id, &treasury);
+    assert_eq!(result, Err(Ok(CrowdfundError::BalanceAboveDustThreshold)));
+    assert_eq!(client.get_balance(&project_id), 500_000);
+}
+
+// ===== withdraw from inactive project test =====
+#[test]
+fn test_withdraw_from_inactive_project() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("Test"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    client.deposit(&user, &project_id, &500_000, &None);
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+
+    // Withdraw works when project is active
+    client.withdraw(&project_id, &100_000);
+
+    // Verify balance after withdrawal
+    let balance = client.get_balance(&project_id);
+    assert_eq!(balance, 400_000);
+}
+
+// ===== multiple deposits to same project =====
+#[test]
+fn test_multiple_deposits() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("Test"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // First deposit
+    client.deposit(&user, &project_id, &200_000, &None);
+    assert_eq!(client.get_balance(&project_id), 200_000);
+
+    // Second deposit
+    client.deposit(&user, &project_id, &300_000, &None);
+    assert_eq!(client.get_balance(&project_id), 500_000);
+
+    // Verify total deposited
+    let project = client.get_project(&project_id);
+    assert_eq!(project.total_deposited, 500_000);
+}
+
+// ===== partial milestone withdrawal =====
+#[test]
+fn test_partial_withdrawal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("Test"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Deposit more than target
+    client.deposit(&user, &project_id, &1_500_000, &None);
+    assert_eq!(client.get_balance(&project_id), 1_500_000);
+
+    client.submit_milestone(&project_id, &0);
+
+    client.approve_milestone(&admin, &project_id, &0);
+
+    // Withdraw partial amount
+    client.withdraw(&project_id, &500_000);
+    assert_eq!(client.get_balance(&project_id), 1_000_000);
+
+    // Withdraw remaining
+    client.withdraw(&project_id, &1_000_000);
+    assert_eq!(client.get_balance(&project_id), 0);
+
+    let project = client.get_project(&project_id);
+    assert_eq!(project.total_withdrawn, 1_500_000);
+}
+
+// ===== unauthorized owner withdrawal attempt =====
+#[test]
+fn test_unauthorized_withdrawal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("Test"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    client.deposit(&user, &project_id, &500_000, &None);
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+
+    // User (non-owner) tries to withdraw - should fail due to authorization
+    // The contract checks owner.require_auth() so it will panic
+    // We verify this by checking that only owner can call withdraw
+}
+
+// ===== milestone approval then check status =====
+#[test]
+fn test_milestone_approval_status() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("Test"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Before approval
+    assert!(!client.is_milestone_approved(&project_id));
+
+    // Approve milestone
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+
+    // After approval
+    assert!(client.is_milestone_approved(&project_id));
+}
+
+// ===== get_balance after operations =====
+#[test]
+fn test_balance_tracking() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("Test"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Initial balance should be 0
+    assert_eq!(client.get_balance(&project_id), 0);
+
+    // After deposit
+    client.deposit(&user, &project_id, &100_000, &None);
+    assert_eq!(client.get_balance(&project_id), 100_000);
+
+    // After approval and withdrawal
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+    client.withdraw(&project_id, &50_000);
+    assert_eq!(client.get_balance(&project_id), 50_000);
+}
+
+// ===== project data integrity after operations =====
+#[test]
+fn test_project_data_integrity() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &2_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Verify initial project data
+    let project = client.get_project(&project_id);
+    assert_eq!(project.id, project_id);
+    assert_eq!(project.owner, owner);
+    assert_eq!(project.name, symbol_short!("TestProj"));
+    assert_eq!(project.target_amount, 2_000_000);
+    assert_eq!(project.total_deposited, 0);
+    assert_eq!(project.total_withdrawn, 0);
+    assert!(project.is_active);
+
+    // After deposit
+    client.deposit(&user, &project_id, &500_000, &None);
+    let project_after_deposit = client.get_project(&project_id);
+    assert_eq!(project_after_deposit.total_deposited, 500_000);
+
+    // After approval and withdrawal
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+    client.withdraw(&project_id, &200_000);
+    let project_after_withdrawal = client.get_project(&project_id);
+    assert_eq!(project_after_withdrawal.total_withdrawn, 200_000);
+}
+
+// ===== zero target amount project =====
+#[test]
+fn test_create_project_invalid_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, _) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    // An account address is not a token contract
+    let bogus_token = Address::generate(&env);
+    let result = client.try_create_project(
+        &owner,
+        &symbol_short!("Bogus"),
+        &1_000_000,
+        &bogus_token,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidToken)));
+}
+
+#[test]
+fn test_set_project_token_before_deposits() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    let (new_token, new_token_admin) = create_token_contract(&env, &admin);
+    client.set_project_token(&project_id, &new_token.address);
+    assert_eq!(client.get_project_token(&project_id), new_token.address);
+
+    // Deposits now go through the new token
+    let donor = Address::generate(&env);
+    new_token_admin.mint(&donor, &1_000);
+    client.deposit(&donor, &project_id, &1_000, &None);
+    assert_eq!(client.get_balance(&project_id), 1_000);
+    assert_eq!(new_token.balance(&client.address), 1_000);
+}
+
+#[test]
+fn test_set_project_token_after_deposit_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &1_000, &None);
+
+    let (new_token, _) = create_token_contract(&env, &admin);
+    let result = client.try_set_project_token(&project_id, &new_token.address);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectHasDeposits)));
+    assert_eq!(client.get_project_token(&project_id), token_client.address);
+}
+
+#[test]
+fn test_create_project_zero_target() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    // A zero target creates an open-ended project
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("Zero"),
+        &0,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert_eq!(client.get_project(&project_id).target_amount, 0);
+
+    // Deposits work and never trigger the target-reached event
+    client.deposit(&user, &project_id, &500_000, &None);
+    assert_eq!(client.get_balance(&project_id), 500_000);
+    assert_eq!(count_events(&env, "target_reached_event"), 0);
+
+    // Milestone withdrawals work as usual
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+    client.withdraw(&project_id, &200_000);
+    assert_eq!(client.get_balance(&project_id), 300_000);
+}
+
+// ===== exact balance withdrawal =====
+#[test]
+fn test_withdraw_exact_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("Test"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    let deposit_amount = 300_000;
+    client.deposit(&user, &project_id, &deposit_amount, &None);
+    assert_eq!(client.get_balance(&project_id), deposit_amount);
+
+    client.submit_milestone(&project_id, &0);
+
+    client.approve_milestone(&admin, &project_id, &0);
+
+    // Withdraw exact balance
+    client.withdraw(&project_id, &deposit_amount);
+    assert_eq!(client.get_balance(&project_id), 0);
+
+    let project = client.get_project(&project_id);
+    assert_eq!(project.total_withdrawn, deposit_amount);
+}
+
+// ===== sequential project creation =====
+#[test]
+fn test_sequential_project_creation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, token_client) = setup_test(&env);
+
+    client.initialize(&admin, &None);
+
+    let owner1 = Address::generate(&env);
+    let owner2 = Address::generate(&env);
+    let owner3 = Address::generate(&env);
+
+    // Create projects sequentially
+    let id1 = client.create_project(
         &owner1,
         &symbol_short!("P1"),
         &100_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let id2 = client.create_project(
+        &owner2,
+        &symbol_short!("P2"),
+        &200_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let id3 = client.create_project(
+        &owner3,
+        &symbol_short!("P3"),
+        &300_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    assert_eq!(id1, 0);
+    assert_eq!(id2, 1);
+    assert_eq!(id3, 2);
+
+    // Verify all projects exist with correct data
+    assert_eq!(client.get_project(&id1).target_amount, 100_000);
+    assert_eq!(client.get_project(&id2).target_amount, 200_000);
+    assert_eq!(client.get_project(&id3).target_amount, 300_000);
+
+    // Verify next project ID is 3
+    // This is tested implicitly through sequential creation
+}
+
+#[test]
+fn test_fund_matching_pool_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    // Non-admin tries to fund matching pool - should fail
+    let result = client.try_fund_matching_pool(&owner, &token_client.address, &10_000_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+}
+
+#[test]
+fn test_calculate_match_single_contributor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    // Create project
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Deposit funds from single contributor
+    let contribution: i128 = 1_000_000; // 1M tokens
+    client.deposit(&user, &project_id, &contribution, &None);
+
+    // Calculate match
+    // sqrt(1_000_000) = 1000
+    // match = 1000^2 = 1_000_000
+    let match_amount = client.calculate_match(&project_id);
+    assert!(match_amount > 0);
+
+    // Verify contributor count
+    assert_eq!(client.get_contributor_count(&project_id), 1);
+
+    // Verify contribution amount
+    assert_eq!(client.get_contribution(&project_id, &user), contribution);
+}
+
+#[test]
+fn test_calculate_match_multiple_contributors() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    // Create project
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Create multiple users
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let user3 = Address::generate(&env);
+
+    // Mint tokens to users
+    let (_, token_admin_client) = create_token_contract(&env, &admin);
+    token_admin_client.mint(&user1, &10_000_000);
+    token_admin_client.mint(&user2, &10_000_000);
+    token_admin_client.mint(&user3, &10_000_000);
+
+    // Different contributions
+    // user1: 100 (sqrt = 10)
+    // user2: 400 (sqrt = 20)
+    // user3: 900 (sqrt = 30)
+    // sum of sqrt = 60
+    // match = 60^2 = 3600
+    client.deposit(&user1, &project_id, &100, &None);
+    client.deposit(&user2, &project_id, &400, &None);
+    client.deposit(&user3, &project_id, &900, &None);
+
+    // Calculate match
+    let match_amount = client.calculate_match(&project_id);
+
+    // Verify match is approximately 3600 (allowing for fixed-point rounding)
+    // sqrt(100) ≈ 10, sqrt(400) = 20, sqrt(900) = 30
+    // sum = 60, match = 3600
+    assert!((3500..=3700).contains(&match_amount));
+
+    // Verify contributor count
+    assert_eq!(client.get_contributor_count(&project_id), 3);
+}
+
+#[test]
+fn test_calculate_match_exponent() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    let user2 = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&user2, &10_000_000);
+
+    // k = 2: (sqrt(100) + sqrt(400))^2 = 900
+    client.deposit(&user, &project_id, &100, &None);
+    client.deposit(&user2, &project_id, &400, &None);
+    assert_eq!(client.get_match_exponent(), 2);
+    let quadratic_match = client.calculate_match(&project_id);
+    assert_eq!(quadratic_match, 900);
+
+    // k = 1 matches the plain sum of contributions, a milder setting
+    client.set_match_exponent(&admin, &1);
+    assert_eq!(client.get_match_exponent(), 1);
+    let linear_match = client.calculate_match(&project_id);
+    assert_eq!(linear_match, 500);
+    assert!(linear_match < quadratic_match);
+
+    // k = 3: (100^(1/3) + 400^(1/3))^3 ≈ 1732
+    client.set_match_exponent(&admin, &3);
+    let cubic_match = client.calculate_match(&project_id);
+    assert!((1700..=1760).contains(&cubic_match));
+}
+
+/// Read the running root sum and a from-scratch recomputation of it
+fn sum_sqrt_pair(
+    env: &Env,
+    client: &CrowdfundVaultContractClient,
+    project_id: u64,
+) -> (i128, i128) {
+    env.as_contract(&client.address, || {
+        let (exponent, precision, stored): (u32, u32, i128) = env
+            .storage()
+            .persistent()
+            .get(&crate::storage::DataKey::SumSqrt(project_id))
+            .unwrap();
+        let recomputed =
+            CrowdfundVaultContract::compute_sum_sqrt(env, project_id, 7, exponent, precision);
+        (stored, recomputed)
+    })
+}
+
+#[test]
+fn test_sum_sqrt_tracks_deposits() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    let user2 = Address::generate(&env);
+    let user3 = Address::generate(&env);
+    let token_admin_client = StellarAssetClient::new(&env, &token_client.address);
+    token_admin_client.mint(&user2, &10_000_000);
+    token_admin_client.mint(&user3, &10_000_000);
+
+    // Repeat deposits replace the contributor's previous root rather than adding to it
+    let deposits = [
+        (&user, 100),
+        (&user2, 400),
+        (&user, 125),
+        (&user3, 900),
+        (&user2, 33),
+        (&user, 1),
+    ];
+    for (depositor, amount) in deposits {
+        client.deposit(depositor, &project_id, &amount, &None);
+        let (stored, recomputed) = sum_sqrt_pair(&env, &client, project_id);
+        assert_eq!(stored, recomputed);
+    }
+
+    // user: 226, user2: 433, user3: 900 -> (15.03 + 20.81 + 30)^2 ≈ 4335
+    let match_amount = client.calculate_match(&project_id);
+    assert!((4300..=4370).contains(&match_amount));
+
+    // After the exponent changes the next deposit rebuilds the sum for the new k
+    client.set_match_exponent(&admin, &1);
+    assert_eq!(client.calculate_match(&project_id), 1559);
+    client.deposit(&user3, &project_id, &41, &None);
+    let (stored, recomputed) = sum_sqrt_pair(&env, &client, project_id);
+    assert_eq!(stored, recomputed);
+    assert_eq!(client.calculate_match(&project_id), 1600);
+}
+
+#[test]
+fn test_set_match_exponent_invalid() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let result = client.try_set_match_exponent(&admin, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidExponent)));
+
+    let result = client.try_set_match_exponent(&admin, &5);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidExponent)));
+
+    let result = client.try_set_match_exponent(&owner, &3);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+}
+
+/// Minimal 18-decimal token; deposits fall back to accounting-only updates
+#[contract]
+pub struct MockToken18;
+
+#[contractimpl]
+impl MockToken18 {
+    pub fn decimals(_env: Env) -> u32 {
+        18
+    }
+
+    pub fn balance(_env: Env, _id: Address) -> i128 {
+        0
+    }
+}
+
+/// Token whose transfers trap once frozen, like a `LumenToken` freeze
+#[contract]
+pub struct MockFreezableToken;
+
+#[contractimpl]
+impl MockFreezableToken {
+    pub fn freeze(env: Env) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("frozen"), &true);
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        7
+    }
+
+    pub fn balance(_env: Env, _id: Address) -> i128 {
+        i128::MAX
+    }
+
+    pub fn transfer(env: Env, _from: Address, _to: Address, _amount: i128) {
+        if env.storage().instance().has(&symbol_short!("frozen")) {
+            panic!("account is frozen");
+        }
+    }
+}
+
+#[test]
+fn test_frozen_token_transfer_fails_gracefully() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let token_address = env.register(MockFreezableToken, ());
+    let token = MockFreezableTokenClient::new(&env, &token_address);
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &5_000, &None);
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+
+    token.freeze();
+
+    // The token's trap surfaces as a contract error and nothing is recorded
+    let result = client.try_withdraw(&project_id, &1_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::TokenTransferFailed)));
+    let result = client.try_deposit(&user, &project_id, &1_000, &None);
+    assert_eq!(result, Err(Ok(CrowdfundError::TokenTransferFailed)));
+    assert_eq!(client.get_balance(&project_id), 5_000);
+    assert_eq!(client.get_contribution(&project_id, &user), 5_000);
+}
+
+#[test]
+fn test_calculate_match_normalizes_decimals() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let token_18 = env.register(MockToken18, ());
+    let project_7 = client.create_project(
+        &owner,
+        &symbol_short!("Proj7"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let project_18 = client.create_project(
+        &owner,
+        &symbol_short!("Proj18"),
+        &1_000_000,
+        &token_18,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let one_7: i128 = 10_000_000;
+    let one_18: i128 = 1_000_000_000_000_000_000;
+
+    // Same human value in both tokens: 100 and 400 units
+    client.deposit(&user1, &project_7, &(100 * one_7), &None);
+    client.deposit(&user2, &project_7, &(400 * one_7), &None);
+    client.deposit(&user1, &project_18, &(100 * one_18), &None);
+    client.deposit(&user2, &project_18, &(400 * one_18), &None);
+
+    // (sqrt(100) + sqrt(400))^2 ≈ 900 units (allowing for fixed-point rounding,
+    // which truncates refined roots downward)
+    let match_7 = client.calculate_match(&project_7);
+    assert!((900 * one_7 - 10..=900 * one_7 + 10).contains(&match_7));
+
+    // Both projects receive the same human value
+    let match_18 = client.calculate_match(&project_18);
+    assert_eq!(match_18, match_7 * (one_18 / one_7));
+}
+
+#[test]
+fn test_sybil_penalty_down_weights_clustered_amounts() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let clustered = client.create_project(
+        &owner,
+        &symbol_short!("Cluster"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let varied = client.create_project(
+        &owner,
+        &symbol_short!("Varied"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Clustered: 3 x 100 (sqrt sum = 30). Varied: 64, 100, 144 (sqrt sum = 30)
+    for amount in [100i128, 100, 100] {
+        client.deposit(&Address::generate(&env), &clustered, &amount, &None);
+    }
+    for amount in [64i128, 100, 144] {
+        client.deposit(&Address::generate(&env), &varied, &amount, &None);
+    }
+
+    // Disabled by default: both projects match the same
+    let clustered_match = client.calculate_match(&clustered);
+    let varied_match = client.calculate_match(&varied);
+    assert!((890..=910).contains(&clustered_match));
+    assert!((890..=910).contains(&varied_match));
+
+    // Halve the weight of clustered contributions: (15)^2 = 225
+    client.set_sybil_penalty(&admin, &5_000);
+    let penalized_match = client.calculate_match(&clustered);
+    assert!((220..=230).contains(&penalized_match));
+    assert_eq!(client.calculate_match(&varied), varied_match);
+
+    let result = client.try_set_sybil_penalty(&admin, &10_001);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidPenalty)));
+}
+
+#[test]
+fn test_calculate_match_no_contributors() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    // Create project
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Calculate match with no contributors
+    let match_amount = client.calculate_match(&project_id);
+    assert_eq!(match_amount, 0);
+}
+
+#[test]
+fn test_get_scale() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _, _, _, _) = setup_test(&env);
+
+    assert_eq!(client.get_scale(), 1_000_000_000);
+}
+
+#[test]
+fn test_saturating_square_boundaries() {
+    use crate::math::saturating_square;
+
+    assert_eq!(saturating_square(0), 0);
+    assert_eq!(saturating_square(-3), 9);
+
+    // Largest value whose square still fits in i128
+    let max_root: i128 = 13_043_817_825_332_782_212;
+    assert_eq!(saturating_square(max_root), max_root * max_root);
+    assert_eq!(saturating_square(max_root + 1), i128::MAX);
+    assert_eq!(saturating_square(i128::MAX), i128::MAX);
+    assert_eq!(saturating_square(i128::MIN), i128::MAX);
+}
+
+#[test]
+fn test_root_and_pow_scaled() {
+    use crate::math::{root_scaled, saturating_pow_scaled, sqrt_scaled, SCALE};
+
+    assert_eq!(root_scaled(400, 2, 2), sqrt_scaled(400, 2));
+    assert_eq!(root_scaled(27, 3, 2), 3 * SCALE);
+    assert_eq!(root_scaled(16, 4, 2), 2 * SCALE);
+    assert_eq!(root_scaled(5, 1, 2), 5 * SCALE);
+    assert_eq!(root_scaled(0, 3, 2), 0);
+
+    assert_eq!(saturating_pow_scaled(2 * SCALE, 3), 8 * SCALE);
+    assert_eq!(saturating_pow_scaled(5 * SCALE, 1), 5 * SCALE);
+    assert_eq!(saturating_pow_scaled(i128::MAX, 3), i128::MAX / SCALE);
+}
+
+#[test]
+fn test_sqrt_scaled_precision() {
+    use crate::math::sqrt_scaled;
+
+    // floor(sqrt(2) * 1e9)
+    let reference: i128 = 1_414_213_562;
+
+    let low = sqrt_scaled(2, 0);
+    let medium = sqrt_scaled(2, 2);
+    let high = sqrt_scaled(2, 8);
+
+    // The bare linear estimate is coarse; each iteration tightens it
+    assert_eq!(low, 1_500_000_000);
+    assert!((medium - reference).abs() < (low - reference).abs());
+    assert!((high - reference).abs() <= (medium - reference).abs());
+    assert_eq!(high, reference);
+
+    // Perfect squares are exact at any precision
+    assert_eq!(sqrt_scaled(400, 0), 20_000_000_000);
+    assert_eq!(sqrt_scaled(400, 8), 20_000_000_000);
+}
+
+#[test]
+fn test_set_sqrt_precision() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+    assert_eq!(client.get_sqrt_precision(), 2);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    // Tiny contributions, where the linear estimate is coarsest:
+    // (sqrt(2) + sqrt(8))^2 = (3 * sqrt(2))^2 = 18
+    let user2 = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&user2, &100);
+    client.deposit(&user, &project_id, &2, &None);
+    client.deposit(&user2, &project_id, &8, &None);
+    let reference: i128 = 18;
+
+    client.set_sqrt_precision(&admin, &0);
+    let low = client.calculate_match(&project_id);
+    client.set_sqrt_precision(&admin, &8);
+    let high = client.calculate_match(&project_id);
+    assert!((high - reference).abs() < (low - reference).abs());
+    assert!((high - reference).abs() <= 1);
+
+    let result = client.try_set_sqrt_precision(&admin, &9);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidPrecision)));
+    let result = client.try_set_sqrt_precision(&owner, &1);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+}
+
+#[test]
+fn test_saturating_sum_boundaries() {
+    use crate::math::saturating_sum;
+
+    assert_eq!(saturating_sum(1, 2), 3);
+    assert_eq!(saturating_sum(i128::MAX - 1, 1), i128::MAX);
+    assert_eq!(saturating_sum(i128::MAX, 1), i128::MAX);
+    assert_eq!(saturating_sum(i128::MAX, i128::MAX), i128::MAX);
+    assert_eq!(saturating_sum(i128::MIN, -1), i128::MIN);
+}
+
+#[test]
+fn test_distribute_match() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    // Create project
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Deposit funds
+    let contribution: i128 = 1_000_000;
+    client.deposit(&user, &project_id, &contribution, &None);
+
+    // Fund matching pool
+    let pool_amount: i128 = 10_000_000;
+    let (_, token_admin_client) = create_token_contract(&env, &admin);
+    token_admin_client.mint(&admin, &pool_amount);
+    client.fund_matching_pool(&admin, &token_client.address, &pool_amount);
+
+    // Get initial balance
+    let initial_balance = client.get_balance(&project_id);
+
+    // Calculate and distribute match
+    let match_amount = client.calculate_match(&project_id);
+    let distributed = client.distribute_match(&admin, &project_id);
+
+    // Verify match was distributed
+    assert!(distributed > 0);
+    assert_eq!(distributed, match_amount);
+
+    // Verify project balance increased
+    let new_balance = client.get_balance(&project_id);
+    assert_eq!(new_balance, initial_balance + distributed);
+
+    // Verify matching pool decreased
+    let remaining_pool = client.get_matching_pool_balance(&token_client.address);
+    assert_eq!(remaining_pool, pool_amount - distributed);
+}
+
+#[test]
+fn test_distribute_match_requires_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &1_000_000, &None);
+    client.fund_matching_pool(&admin, &token_client.address, &100_000);
+
+    let result = client.try_distribute_match(&owner, &project_id);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+    assert_eq!(client.get_balance(&project_id), 1_000_000);
+
+    assert_eq!(client.distribute_match(&admin, &project_id), 100_000);
+    assert_eq!(client.get_balance(&project_id), 1_100_000);
+}
+
+#[test]
+fn test_distribute_match_once_per_round() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &1_000_000, &None);
+    client.fund_matching_pool(&admin, &token_client.address, &300_000);
+
+    assert_eq!(client.distribute_match(&admin, &project_id), 300_000);
+
+    // Replaying the distribution in the same round pays nothing
+    client.fund_matching_pool(&admin, &token_client.address, &300_000);
+    assert_eq!(client.distribute_match(&admin, &project_id), 0);
+    assert_eq!(
+        client.distribute_matches(&admin, &vec![&env, project_id]),
+        vec![&env, 0]
+    );
+    assert_eq!(client.get_balance(&project_id), 1_300_000);
+
+    // A new round allows matching again
+    assert_eq!(client.start_match_round(&admin), 1);
+    assert_eq!(client.distribute_match(&admin, &project_id), 300_000);
+}
+
+#[test]
+fn test_distribute_match_min_pool_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &1_000_000, &None);
+
+    let result = client.try_set_min_pool_balance(&admin, &-1);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
+    client.set_min_pool_balance(&admin, &10_000);
+    assert_eq!(client.get_min_pool_balance(), 10_000);
+
+    // A nearly-empty pool is refused
+    client.fund_matching_pool(&admin, &token_client.address, &9_999);
+    let result = client.try_distribute_match(&admin, &project_id);
+    assert_eq!(result, Err(Ok(CrowdfundError::MatchingPoolTooLow)));
+    assert!(!client.is_match_eligible(&project_id));
+    assert_eq!(
+        client.distribute_matches(&admin, &vec![&env, project_id]),
+        vec![&env, 0]
+    );
+
+    // Once the pool reaches the minimum, distribution goes ahead
+    client.fund_matching_pool(&admin, &token_client.address, &1);
+    assert!(client.is_match_eligible(&project_id));
+    assert_eq!(client.distribute_match(&admin, &project_id), 10_000);
+}
+
+#[test]
+fn test_get_current_round_and_round_data() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    // No round has been started yet
+    assert_eq!(client.get_current_round(), 0);
+    assert_eq!(
+        client.try_get_round(&0),
+        Err(Ok(CrowdfundError::RoundNotFound))
+    );
+    assert_eq!(
+        client.try_get_round(&1),
+        Err(Ok(CrowdfundError::RoundNotFound))
+    );
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &1_000_000, &None);
+    client.fund_matching_pool(&admin, &token_client.address, &300_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 5_000);
+    let round_id = client.start_match_round(&admin);
+    assert_eq!(client.get_current_round(), round_id);
+
+    let round = client.get_round(&round_id);
+    assert_eq!(round.id, round_id);
+    assert_eq!(round.started_at, 5_000);
+    assert_eq!(round.projects_matched, 0);
+    assert_eq!(round.total_distributed, 0);
+
+    // Distributions are recorded against the active round
+    assert_eq!(client.distribute_match(&admin, &project_id), 300_000);
+    let round = client.get_round(&round_id);
+    assert_eq!(round.projects_matched, 1);
+    assert_eq!(round.total_distributed, 300_000);
+
+    // Starting another round leaves the previous one's data intact
+    let next_round = client.start_match_round(&admin);
+    assert_eq!(client.get_current_round(), next_round);
+    assert_eq!(client.get_round(&next_round).total_distributed, 0);
+    assert_eq!(client.get_round(&round_id).total_distributed, 300_000);
+}
+
+#[test]
+fn test_get_effective_contribution() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let user2 = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&user2, &10_000_000);
+
+    // sqrt weights 10 and 20, match = (10 + 20)^2 = 900
+    client.deposit(&user, &project_id, &100, &None);
+    client.deposit(&user2, &project_id, &400, &None);
+
+    // Before any match only the direct contribution counts
+    assert_eq!(client.get_effective_contribution(&project_id, &user), 100);
+
+    client.fund_matching_pool(&admin, &token_client.address, &1_000_000);
+    assert_eq!(client.distribute_match(&admin, &project_id), 900);
+
+    let effective_1 = client.get_effective_contribution(&project_id, &user);
+    let effective_2 = client.get_effective_contribution(&project_id, &user2);
+    assert_eq!(effective_1, 100 + 300);
+    assert_eq!(effective_2, 400 + 600);
+    assert_eq!(
+        effective_1 + effective_2,
+        100 + 400 + client.get_total_matched(&project_id)
+    );
+
+    // Non-contributors have no impact
+    let stranger = Address::generate(&env);
+    assert_eq!(client.get_effective_contribution(&project_id, &stranger), 0);
+}
+
+#[test]
+fn test_get_total_matched_accumulates() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &1_000_000, &None);
+    assert_eq!(client.get_total_matched(&project_id), 0);
+
+    // First round is capped by a small pool
+    client.fund_matching_pool(&admin, &token_client.address, &100_000);
+    let first = client.distribute_match(&admin, &project_id);
+    assert_eq!(first, 100_000);
+
+    // Second round
+    client.start_match_round(&admin);
+    client.fund_matching_pool(&admin, &token_client.address, &200_000);
+    let second = client.distribute_match(&admin, &project_id);
+    assert_eq!(second, 200_000);
+
+    assert_eq!(client.get_total_matched(&project_id), first + second);
+
+    // Organic deposits are not counted as matching
+    client.deposit(&user, &project_id, &50_000, &None);
+    assert_eq!(client.get_total_matched(&project_id), first + second);
+}
+
+#[test]
+fn test_distribute_match_with_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let collector = Address::generate(&env);
+    client.set_match_fee(&admin, &250, &collector); // 2.5%
+    assert_eq!(client.get_match_fee_bps(), 250);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &1_000_000, &None);
+
+    // Back the matching pool with real tokens held by the vault
+    let pool_amount: i128 = 400_000;
+    let token_admin_client = StellarAssetClient::new(&env, &token_client.address);
+    token_admin_client.mint(&client.address, &pool_amount);
+    client.fund_matching_pool(&admin, &token_client.address, &pool_amount);
+
+    let distributed = client.distribute_match(&admin, &project_id);
+
+    let fee = pool_amount * 250 / 10_000;
+    assert_eq!(distributed, pool_amount - fee);
+    assert_eq!(
+        client.get_balance(&project_id),
+        1_000_000 + pool_amount - fee
+    );
+    assert_eq!(token_client.balance(&collector), fee);
+    assert_eq!(client.get_matching_pool_balance(&token_client.address), 0);
+}
+
+#[test]
+fn test_fee_collectors_are_independent() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    // Each fee is configured with its own collector, in any order
+    let match_collector = Address::generate(&env);
+    let deposit_collector = Address::generate(&env);
+    let registration_collector = Address::generate(&env);
+    client.set_match_fee(&admin, &250, &match_collector);
+    client.set_deposit_fee(&admin, &100, &deposit_collector);
+    client.set_registration_fee(
+        &admin,
+        &5_000,
+        &token_client.address,
+        &registration_collector,
+    );
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.register_contributor(&user);
+    client.deposit(&user, &project_id, &1_000_000, &None);
+
+    let pool_amount: i128 = 400_000;
+    StellarAssetClient::new(&env, &token_client.address).mint(&client.address, &pool_amount);
+    client.fund_matching_pool(&admin, &token_client.address, &pool_amount);
+    client.distribute_match(&admin, &project_id);
+
+    assert_eq!(token_client.balance(&registration_collector), 5_000);
+    assert_eq!(token_client.balance(&deposit_collector), 10_000);
+    assert_eq!(
+        token_client.balance(&match_collector),
+        pool_amount * 250 / 10_000
+    );
+}
+
+#[test]
+fn test_distribute_match_zero_fee_default() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+    assert_eq!(client.get_match_fee_bps(), 0);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &1_000_000, &None);
+    client.fund_matching_pool(&admin, &token_client.address, &400_000);
+
+    // Without a configured fee the project receives the full match
+    assert_eq!(client.distribute_match(&admin, &project_id), 400_000);
+    assert_eq!(client.get_balance(&project_id), 1_400_000);
+}
+
+#[test]
+fn test_deposit_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let user2 = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&user2, &10_000_000);
+
+    let collector = Address::generate(&env);
+    assert_eq!(client.get_deposit_fee_bps(), 0);
+    client.set_deposit_fee(&admin, &250, &collector);
+    assert_eq!(client.get_deposit_fee_bps(), 250);
+
+    // 2.5% of each deposit goes to the collector, the rest is credited
+    client.deposit(&user, &project_id, &10_000, &None);
+    assert_eq!(token_client.balance(&collector), 250);
+    assert_eq!(token_client.balance(&user), 10_000_000 - 10_000);
+    assert_eq!(token_client.balance(&client.address), 9_750);
+    assert_eq!(client.get_contribution(&project_id, &user), 9_750);
+    assert_eq!(client.get_balance(&project_id), 9_750);
+    assert_eq!(client.get_project(&project_id).total_deposited, 9_750);
+
+    // Matching uses net contributions: (sqrt(9_750) + sqrt(39_000))^2 = 87_750,
+    // not the 90_000 the gross amounts would give
+    client.deposit(&user2, &project_id, &40_000, &None);
+    assert_eq!(client.get_contribution(&project_id, &user2), 39_000);
+    assert!((87_700..=87_800).contains(&client.calculate_match(&project_id)));
+
+    let result = client.try_set_deposit_fee(&admin, &10_001, &collector);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidFee)));
+    let result = client.try_set_deposit_fee(&owner, &100, &collector);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+
+    // Configuring the match fee does not redirect deposit fees
+    let match_collector = Address::generate(&env);
+    client.set_match_fee(&admin, &100, &match_collector);
+    client.deposit(&user, &project_id, &10_000, &None);
+    assert_eq!(token_client.balance(&collector), 250 + 1_000 + 250);
+    assert_eq!(token_client.balance(&match_collector), 0);
+
+    // A deposit entirely consumed by the fee is rejected
+    client.set_deposit_fee(&admin, &10_000, &collector);
+    let result = client.try_deposit(&user, &project_id, &10_000, &None);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
+}
+
+#[test]
+fn test_set_match_fee_invalid() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let collector = Address::generate(&env);
+    let result = client.try_set_match_fee(&admin, &10_001, &collector);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidFee)));
+
+    let result = client.try_set_match_fee(&owner, &100, &collector);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+}
+
+#[test]
+fn test_distribute_matches_multiple_projects() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_a = client.create_project(
+        &owner,
+        &symbol_short!("ProjA"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let project_b = client.create_project(
+        &owner,
+        &symbol_short!("ProjB"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let project_c = client.create_project(
+        &owner,
+        &symbol_short!("ProjC"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    client.deposit(&user, &project_a, &10_000, &None);
+    client.deposit(&user, &project_b, &40_000, &None);
+    client.deposit(&user, &project_c, &90_000, &None);
+
+    let match_a = client.calculate_match(&project_a);
+    let match_b = client.calculate_match(&project_b);
+
+    // Pool covers A in full, B partially and nothing is left for C
+    let pool_amount = match_a + match_b / 2;
+    client.fund_matching_pool(&admin, &token_client.address, &pool_amount);
+
+    let distributed =
+        client.distribute_matches(&admin, &vec![&env, project_a, project_b, project_c]);
+
+    assert_eq!(distributed, vec![&env, match_a, match_b / 2, 0]);
+    assert_eq!(client.get_balance(&project_a), 10_000 + match_a);
+    assert_eq!(client.get_balance(&project_b), 40_000 + match_b / 2);
+    assert_eq!(client.get_balance(&project_c), 90_000);
+    assert_eq!(client.get_matching_pool_balance(&token_client.address), 0);
+}
+
+#[test]
+fn test_is_match_eligible_min_contributors() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.set_min_match_contributors(&admin, &2);
+    client.fund_matching_pool(&admin, &token_client.address, &1_000_000);
+
+    client.deposit(&user, &project_id, &10_000, &None);
+    assert!(!client.is_match_eligible(&project_id));
+    assert_eq!(client.distribute_match(&admin, &project_id), 0);
+
+    // A second contributor meets the threshold
+    let user2 = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&user2, &10_000);
+    client.deposit(&user2, &project_id, &10_000, &None);
+    assert!(client.is_match_eligible(&project_id));
+
+    // Closed projects are never eligible
+    client.close_project(&admin, &project_id);
+    assert!(!client.is_match_eligible(&project_id));
+}
+
+#[test]
+fn test_is_match_eligible_requires_pool_funds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &10_000, &None);
+    assert!(!client.is_match_eligible(&project_id));
+
+    client.fund_matching_pool(&admin, &token_client.address, &1_000);
+    assert!(client.is_match_eligible(&project_id));
+
+    let result = client.try_is_match_eligible(&99);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
+}
+
+#[test]
+fn test_max_match_ratio_caps_match() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Ten contributors of 100 each: match = (10 * sqrt(100))^2 = 10_000, ten times the deposits
+    let token_admin_client = StellarAssetClient::new(&env, &token_client.address);
+    for _ in 0..10 {
+        let contributor = Address::generate(&env);
+        token_admin_client.mint(&contributor, &100);
+        client.deposit(&contributor, &project_id, &100, &None);
+    }
+    assert_eq!(client.calculate_match(&project_id), 10_000);
+
+    let result = client.try_set_max_match_ratio(&owner, &20_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+    client.set_max_match_ratio(&admin, &20_000);
+    assert_eq!(client.get_max_match_ratio_bps(), 20_000);
+
+    // A 200% cap limits the match to twice the 1_000 deposited
+    client.fund_matching_pool(&admin, &token_client.address, &50_000);
+    assert_eq!(client.distribute_match(&admin, &project_id), 2_000);
+    assert_eq!(client.get_total_matched(&project_id), 2_000);
+
+    // Earlier matching doesn't count as organic support in later rounds
+    client.start_match_round(&admin);
+    assert_eq!(client.distribute_match(&admin, &project_id), 2_000);
+    assert_eq!(client.get_total_matched(&project_id), 4_000);
+}
+
+#[test]
+fn test_distribute_round_spends_pool_exactly() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let mut project_ids = vec![&env];
+    for amount in [100, 200, 400] {
+        let project_id = client.create_project(
+            &owner,
+            &symbol_short!("TestProj"),
+            &1_000_000,
+            &token_client.address,
+            &None,
+            &symbol_short!("General"),
+        );
+        client.deposit(&user, &project_id, &amount, &None);
+        project_ids.push_back(project_id);
+    }
+    let mut matches: Vec<i128> = vec![&env];
+    for project_id in project_ids.iter() {
+        matches.push_back(client.calculate_match(&project_id));
+    }
+    let total_match: i128 = matches.iter().sum();
+
+    // The pool covers only part of the matches, which don't divide it evenly
+    client.fund_matching_pool(&admin, &token_client.address, &100);
+    let round_id = client.start_match_round(&admin);
+    let distributed = client.distribute_round(&admin, &token_client.address, &project_ids);
+
+    // Every unit of the pool is handed out, none left as dust
+    assert_eq!(distributed.iter().sum::<i128>(), 100);
+    assert_eq!(client.get_matching_pool_balance(&token_client.address), 0);
+    assert_eq!(client.get_round(&round_id).total_distributed, 100);
+
+    // Each share is its proportional amount, rounded up or down by at most one
+    for (i, share) in distributed.iter().enumerate() {
+        let exact = 100 * matches.get(i as u32).unwrap() / total_match;
+        assert!(share == exact || share == exact + 1);
+        let project_id = project_ids.get(i as u32).unwrap();
+        assert_eq!(client.get_total_matched(&project_id), share);
+    }
+
+    // Projects already matched this round get nothing more
+    client.fund_matching_pool(&admin, &token_client.address, &100);
+    let again = client.distribute_round(&admin, &token_client.address, &project_ids);
+    assert_eq!(again, vec![&env, 0, 0, 0]);
+}
+
+#[test]
+fn test_distribute_round_with_18_decimal_amounts() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let token_18 = env.register(MockToken18, ());
+    let one_18: i128 = 1_000_000_000_000_000_000;
+    let mut project_ids = vec![&env];
+    for amounts in [[100, 400], [250, 0]] {
+        let project_id = client.create_project(
+            &owner,
+            &symbol_short!("Proj18"),
+            &1_000_000,
+            &token_18,
+            &None,
+            &symbol_short!("General"),
+        );
+        for amount in amounts.into_iter().filter(|amount| *amount > 0) {
+            client.deposit(
+                &Address::generate(&env),
+                &project_id,
+                &(amount * one_18),
+                &None,
+            );
+        }
+        project_ids.push_back(project_id);
+    }
+
+    // spend * weight is far beyond i128 here, but the shares are still exact
+    let pool = 700 * one_18 + 1;
+    client.fund_matching_pool(&admin, &token_18, &pool);
+    let distributed = client.distribute_round(&admin, &token_18, &project_ids);
+
+    assert_eq!(distributed.iter().sum::<i128>(), pool);
+    assert_eq!(client.get_matching_pool_balance(&token_18), 0);
+    let (first, second) = (distributed.get(0).unwrap(), distributed.get(1).unwrap());
+    assert!(first > second * 3 && first < second * 4);
+}
+
+#[test]
+fn test_distribute_round_pool_larger_than_matches() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &400, &None);
+    client.fund_matching_pool(&admin, &token_client.address, &1_000);
+
+    // Listing a project twice doesn't double its share
+    let distributed = client.distribute_round(
+        &admin,
+        &token_client.address,
+        &vec![&env, project_id, project_id],
+    );
+    assert_eq!(distributed, vec![&env, 400, 0]);
+    assert_eq!(client.get_matching_pool_balance(&token_client.address), 600);
+
+    let (other_token, _) = create_token_contract(&env, &admin);
+    let result = client.try_distribute_round(&admin, &other_token.address, &vec![&env, project_id]);
+    assert_eq!(result, Err(Ok(CrowdfundError::TokenMismatch)));
+}
+
+#[test]
+fn test_distribute_matches_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let result = client.try_distribute_matches(&owner, &vec![&env, 0u64]);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+}
+
+#[test]
+fn test_contributor_registration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, user, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    assert!(!client.is_registered(&user));
+
+    // Register contributor
+    client.register_contributor(&user);
+    assert!(client.is_registered(&user));
+
+    // Verify reputation is 0
+    assert_eq!(client.get_reputation(&user), 0);
+
+    // Try to register again - should fail
+    let result = client.try_register_contributor(&user);
+    assert_eq!(result, Err(Ok(CrowdfundError::AlreadyRegistered)));
+}
+
+#[test]
+fn test_get_top_reputation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+    assert_eq!(client.get_top_reputation(&5), vec![&env]);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let dave = Address::generate(&env);
+    for (contributor, reputation) in [(&alice, 30), (&bob, 75), (&carol, 0), (&dave, 75)] {
+        client.register_contributor(contributor);
+        client.update_reputation(&admin, contributor, &reputation);
+    }
+
+    // Highest first; equal reputations keep registration order
+    assert_eq!(
+        client.get_top_reputation(&3),
+        vec![
+            &env,
+            (bob.clone(), 75),
+            (dave.clone(), 75),
+            (alice.clone(), 30)
+        ]
+    );
+    assert_eq!(client.get_top_reputation(&10).len(), 4);
+    assert_eq!(client.get_top_reputation(&0), vec![&env]);
+
+    // Rankings follow reputation changes
+    client.update_reputation(&admin, &carol, &100);
+    assert_eq!(
+        client.get_top_reputation(&2),
+        vec![&env, (carol.clone(), 100), (bob.clone(), 75)]
+    );
+}
+
+#[test]
+fn test_get_top_reputation_is_bounded() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    for i in 0..30 {
+        let contributor = Address::generate(&env);
+        client.register_contributor(&contributor);
+        client.update_reputation(&admin, &contributor, &i);
+    }
+
+    assert_eq!(client.get_registered_contributor_count(), 30);
+    let top = client.get_top_reputation(&u32::MAX);
+    assert_eq!(top.len(), 25);
+    assert_eq!(top.get(0).unwrap().1, 29);
+    assert_eq!(top.get(24).unwrap().1, 5);
+
+    // A contributor outside the board joins it by outscoring the last entry
+    let newcomer = Address::generate(&env);
+    client.register_contributor(&newcomer);
+    assert_eq!(client.get_top_reputation(&u32::MAX).len(), 25);
+    client.batch_update_reputation(&admin, &vec![&env, newcomer.clone()], &vec![&env, 100]);
+    let top = client.get_top_reputation(&u32::MAX);
+    assert_eq!(top.len(), 25);
+    assert_eq!(top.get(0).unwrap(), (newcomer.clone(), 100));
+    assert_eq!(top.get(24).unwrap().1, 6);
+
+    // A ranked contributor whose reputation falls moves down the board
+    client.update_reputation(&admin, &newcomer, &-90);
+    let top = client.get_top_reputation(&u32::MAX);
+    assert_eq!(top.get(0).unwrap().1, 29);
+    assert_eq!(top.get(20).unwrap(), (newcomer, 10));
+    assert_eq!(top.get(24).unwrap().1, 6);
+}
+
+#[test]
+fn test_registration_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let collector = Address::generate(&env);
+
+    // Free by default
+    assert_eq!(client.get_registration_fee(), 0);
+    client.register_contributor(&owner);
+    assert!(client.is_registered(&owner));
+
+    // Paid registration moves the fee to the collector
+    client.set_registration_fee(&admin, &5_000, &token_client.address, &collector);
+    assert_eq!(client.get_registration_fee(), 5_000);
+    client.register_contributor(&user);
+    assert!(client.is_registered(&user));
+    assert_eq!(token_client.balance(&user), 10_000_000 - 5_000);
+    assert_eq!(token_client.balance(&collector), 5_000);
+
+    // Configuring the deposit fee does not redirect registration fees
+    let deposit_collector = Address::generate(&env);
+    client.set_deposit_fee(&admin, &100, &deposit_collector);
+    let member = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&member, &5_000);
+    client.register_contributor(&member);
+    assert_eq!(token_client.balance(&collector), 10_000);
+    assert_eq!(token_client.balance(&deposit_collector), 0);
+
+    // Setting the fee back to zero restores free registration
+    client.set_registration_fee(&admin, &0, &token_client.address, &collector);
+    let newcomer = Address::generate(&env);
+    client.register_contributor(&newcomer);
+    assert_eq!(token_client.balance(&collector), 10_000);
+}
+
+#[test]
+fn test_failed_registration_keeps_no_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let collector = Address::generate(&env);
+    client.set_registration_fee(&admin, &5_000, &token_client.address, &collector);
+
+    // A contributor who can't cover the fee is neither charged nor registered
+    let poor = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&poor, &4_999);
+    let result = client.try_register_contributor(&poor);
+    assert_eq!(result, Err(Ok(CrowdfundError::TokenTransferFailed)));
+    assert!(!client.is_registered(&poor));
+    assert_eq!(token_client.balance(&poor), 4_999);
+    assert_eq!(token_client.balance(&collector), 0);
+
+    // A rejected repeat registration doesn't charge the fee a second time
+    client.register_contributor(&user);
+    let result = client.try_register_contributor(&user);
+    assert_eq!(result, Err(Ok(CrowdfundError::AlreadyRegistered)));
+    assert_eq!(token_client.balance(&user), 10_000_000 - 5_000);
+    assert_eq!(token_client.balance(&collector), 5_000);
+}
+
+#[test]
+fn test_set_registration_fee_invalid() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let result = client.try_set_registration_fee(&admin, &-1, &token_client.address, &admin);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidFee)));
+
+    let result = client.try_set_registration_fee(&owner, &100, &token_client.address, &owner);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+}
+
+#[test]
+fn test_reputation_management() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, user, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    // Register contributor first
+    client.register_contributor(&user);
+
+    // Update reputation
+    client.update_reputation(&admin, &user, &100);
+    assert_eq!(client.get_reputation(&user), 100);
+
+    // Decrease reputation
+    client.update_reputation(&admin, &user, &-50);
+    assert_eq!(client.get_reputation(&user), 50);
+
+    // Non-admin cannot update reputation
+    let non_admin = Address::generate(&env);
+    let result = client.try_update_reputation(&non_admin, &user, &100);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+}
+
+#[test]
+fn test_batch_update_reputation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    client.register_contributor(&alice);
+    client.register_contributor(&bob);
+    client.register_contributor(&carol);
+    client.update_reputation(&admin, &carol, &40);
+
+    client.batch_update_reputation(
+        &admin,
+        &vec![&env, alice.clone(), bob.clone(), carol.clone()],
+        &vec![&env, 10, 25, -15],
+    );
+
+    assert_eq!(client.get_reputation(&alice), 10);
+    assert_eq!(client.get_reputation(&bob), 25);
+    assert_eq!(client.get_reputation(&carol), 25);
+}
+
+#[test]
+fn test_batch_update_reputation_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let alice = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    client.register_contributor(&alice);
+
+    // Mismatched lengths
+    let result =
+        client.try_batch_update_reputation(&admin, &vec![&env, alice.clone()], &vec![&env, 1, 2]);
+    assert_eq!(result, Err(Ok(CrowdfundError::LengthMismatch)));
+
+    // An unregistered contributor rejects the whole batch
+    let result = client.try_batch_update_reputation(
+        &admin,
+        &vec![&env, alice.clone(), stranger],
+        &vec![&env, 5, 5],
+    );
+    assert_eq!(result, Err(Ok(CrowdfundError::ContributorNotFound)));
+    assert_eq!(client.get_reputation(&alice), 0);
+}
+
+#[test]
+fn test_events_emission() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _user, token_client) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    // Create project
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Deposit funds from multiple users to create large match
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let (_, token_admin_client) = create_token_contract(&env, &admin);
+    token_admin_client.mint(&user1, &10_000_000);
+    token_admin_client.mint(&user2, &10_000_000);
+
+    // Large contributions that will create a large match
+    client.deposit(&user1, &project_id, &1_000_000, &None);
+    client.deposit(&user2, &project_id, &1_000_000, &None);
+
+    // Fund matching pool with small amount
+    let pool_amount: i128 = 100_000; // Less than the calculated match
+    token_admin_client.mint(&admin, &pool_amount);
+    client.fund_matching_pool(&admin, &token_client.address, &pool_amount);
+
+    // Calculate match (should be large)
+    let match_amount = client.calculate_match(&project_id);
+    assert!(match_amount > pool_amount);
+
+    // Distribute match (should only distribute what's available)
+    let distributed = client.distribute_match(&admin, &project_id);
+
+    // Should only distribute the pool amount, not the full match
+    assert_eq!(distributed, pool_amount);
+
+    // Verify pool is empty
+    assert_eq!(client.get_matching_pool_balance(&token_client.address), 0);
+}
+
+#[test]
+fn test_multiple_contributions_same_user() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    // Create project
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Same user makes multiple contributions
+    client.deposit(&user, &project_id, &100, &None);
+    client.deposit(&user, &project_id, &300, &None); // Total: 400
+
+    // Should only count as one contributor
+    assert_eq!(client.get_contributor_count(&project_id), 1);
+
+    // Total contribution should be 400
+    assert_eq!(client.get_contribution(&project_id, &user), 400);
+
+    // Calculate match: sqrt(400) = 20, match = 20^2 = 400
+    let match_amount = client.calculate_match(&project_id);
+    // Should be approximately 400 (allowing for rounding)
+    assert!((390..=410).contains(&match_amount));
+    // Deposit
+    client.deposit(&user, &project_id, &500_000, &None);
+
+    // Register contributor
+    client.register_contributor(&user);
+
+    // Update reputation
+    client.update_reputation(&admin, &user, &10);
+
+    // Verify events exist (at least one event should be present)
+    let events = env.events().all();
+    assert!(
+        !events.is_empty(),
+        "Expected at least one event to be emitted"
+    );
+}
+
+fn count_events(env: &Env, name: &str) -> u32 {
+    let name = Symbol::new(env, name);
+    env.events()
+        .all()
+        .iter()
+        .filter(|(_, topics, _)| {
+            topics
+                .get(0)
+                .and_then(|topic| Symbol::try_from_val(env, &topic).ok())
+                == Some(name.clone())
+        })
+        .count() as u32
+}
+
+/// Names of the events `contract` emitted in the most recent invocation, in order
+fn event_names(env: &Env, contract: &Address) -> Vec<Symbol> {
+    let mut names = Vec::new(env);
+    for (emitter, topics, _) in env.events().all().iter() {
+        if emitter != *contract {
+            continue;
+        }
+        if let Some(name) = topics
+            .get(0)
+            .and_then(|topic| Symbol::try_from_val(env, &topic).ok())
+        {
+            names.push_back(name);
+        }
+    }
+    names
+}
+
+/// The `seq` field of the last event emitted by the most recent invocation
+fn last_event_seq(env: &Env) -> u64 {
+    let (_, _, data) = env.events().all().last().unwrap();
+    let data = Map::<Symbol, Val>::try_from_val(env, &data).unwrap();
+    u64::try_from_val(env, &data.get(Symbol::new(env, "seq")).unwrap()).unwrap()
+}
+
+#[test]
+fn test_event_seq_increments() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    assert_eq!(client.get_event_seq(), 0);
+
+    client.initialize(&admin, &None);
+    assert_eq!(last_event_seq(&env), 1);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert_eq!(last_event_seq(&env), 2);
+
+    client.deposit(&user, &project_id, &1_000, &None);
+    assert_eq!(last_event_seq(&env), 3);
+
+    client.submit_milestone(&project_id, &0);
+    assert_eq!(last_event_seq(&env), 4);
+
+    client.approve_milestone(&admin, &project_id, &0);
+    assert_eq!(last_event_seq(&env), 5);
+
+    assert_eq!(client.get_event_seq(), 5);
+}
+
+#[test]
+fn test_matching_pool_paths_advance_event_seq() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let mut ids = Vec::new(&env);
+    for name in [symbol_short!("ProjA"), symbol_short!("ProjB")] {
+        let project_id = client.create_project(
+            &owner,
+            &name,
+            &1_000_000,
+            &token_client.address,
+            &None,
+            &symbol_short!("General"),
+        );
+        client.deposit(&user, &project_id, &10_000, &None);
+        ids.push_back(project_id);
+    }
+    StellarAssetClient::new(&env, &token_client.address).mint(&client.address, &20_000);
+
+    // Crediting the pool is reported like any other pool funding
+    let seq = client.get_event_seq();
+    client.fund_matching_pool(&admin, &token_client.address, &10_000);
+    assert_eq!(
+        event_names(&env, &client.address),
+        vec![&env, Symbol::new(&env, "matching_pool_funded_event")]
+    );
+    assert_eq!(client.get_event_seq(), seq + 1);
+
+    // Each match moved into a project emits a distribution event
+    client.distribute_match(&admin, &ids.get(0).unwrap());
+    assert_eq!(
+        event_names(&env, &client.address),
+        vec![&env, Symbol::new(&env, "match_distributed_event")]
+    );
+    assert_eq!(client.get_event_seq(), seq + 2);
+
+    client.fund_matching_pool(&admin, &token_client.address, &10_000);
+    client.distribute_round(
+        &admin,
+        &token_client.address,
+        &vec![&env, ids.get(1).unwrap()],
+    );
+    assert_eq!(
+        event_names(&env, &client.address),
+        vec![&env, Symbol::new(&env, "match_distributed_event")]
+    );
+    assert_eq!(client.get_event_seq(), seq + 4);
+}
+
+#[test]
+fn test_target_reached_event_fires_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    // Still below target
+    client.deposit(&user, &project_id, &600_000, &None);
+    assert_eq!(count_events(&env, "target_reached_event"), 0);
+
+    // Crosses the target
+    client.deposit(&user, &project_id, &500_000, &None);
+    assert_eq!(count_events(&env, "target_reached_event"), 1);
+
+    // Already above target, no further event
+    client.deposit(&user, &project_id, &100_000, &None);
+    assert_eq!(count_events(&env, "target_reached_event"), 0);
+}
+
+#[test]
+fn test_fund_matching_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, token_client) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    // Fund matching pool
+    let pool_amount: i128 = 10_000_000;
+    client.fund_matching_pool(&admin, &token_client.address, &pool_amount);
+
+    // Verify matching pool balance
+    assert_eq!(
+        client.get_matching_pool_balance(&token_client.address),
+        pool_amount
+    );
+}
+
+#[test]
+fn test_contribute_to_matching_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    // Any community member can top up the pool
+    let donor = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&donor, &50_000);
+
+    client.contribute_to_matching_pool(&donor, &token_client.address, &20_000);
+    assert_eq!(count_events(&env, "matching_pool_funded_event"), 1);
+    assert_eq!(
+        client.get_matching_pool_balance(&token_client.address),
+        20_000
+    );
+    assert_eq!(token_client.balance(&donor), 30_000);
+    assert_eq!(token_client.balance(&client.address), 20_000);
+
+    client.contribute_to_matching_pool(&donor, &token_client.address, &5_000);
+    assert_eq!(
+        client.get_matching_pool_balance(&token_client.address),
+        25_000
+    );
+
+    let result = client.try_contribute_to_matching_pool(&donor, &token_client.address, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
+}
+
+#[test]
+fn test_mint_to_matching_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    // A token administered by the vault itself
+    let (pool_token, _) = create_token_contract(&env, &client.address);
+
+    client.mint_to_matching_pool(&admin, &pool_token.address, &5_000_000);
+    assert_eq!(
+        client.get_matching_pool_balance(&pool_token.address),
+        5_000_000
+    );
+    assert_eq!(pool_token.balance(&client.address), 5_000_000);
+
+    let seq = client.get_event_seq();
+    client.mint_to_matching_pool(&admin, &pool_token.address, &1_000);
+    assert_eq!(count_events(&env, "matching_pool_funded_event"), 1);
+    assert_eq!(last_event_seq(&env), seq + 1);
+    assert_eq!(
+        client.get_matching_pool_balance(&pool_token.address),
+        5_001_000
+    );
+    assert_eq!(pool_token.balance(&client.address), 5_001_000);
+
+    let result = client.try_mint_to_matching_pool(&admin, &pool_token.address, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidAmount)));
+}
+
+fn deposit_commitment(env: &Env, project_id: u64, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::new(env);
+    preimage.extend_from_array(&project_id.to_be_bytes());
+    preimage.extend_from_array(&salt.to_array());
+    env.crypto().sha256(&preimage).into()
+}
+
+#[test]
+fn test_commit_deposit_locks_funds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    let salt = BytesN::from_array(&env, &[7u8; 32]);
+    let commitment = deposit_commitment(&env, project_id, &salt);
+    client.commit_deposit(&user, &commitment, &token_client.address, &250_000);
+
+    // Funds are held by the vault but not yet attributed to any project
+    assert_eq!(token_client.balance(&client.address), 250_000);
+    assert_eq!(token_client.balance(&user), 10_000_000 - 250_000);
+    assert_eq!(client.get_balance(&project_id), 0);
+    assert_eq!(client.get_contributor_count(&project_id), 0);
+
+    // The same commitment cannot be reused
+    let result = client.try_commit_deposit(&user, &commitment, &token_client.address, &1);
+    assert_eq!(result, Err(Ok(CrowdfundError::CommitmentAlreadyExists)));
+}
+
+#[test]
+fn test_reveal_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    let salt = BytesN::from_array(&env, &[7u8; 32]);
+    let commitment = deposit_commitment(&env, project_id, &salt);
+    client.commit_deposit(&user, &commitment, &token_client.address, &250_000);
+
+    client.reveal_deposit(&user, &project_id, &salt);
+
+    // Revealed funds are attributed to the project
+    assert_eq!(client.get_balance(&project_id), 250_000);
+    assert_eq!(client.get_contribution(&project_id, &user), 250_000);
+    assert_eq!(client.get_contributor_count(&project_id), 1);
+    assert_eq!(client.get_project(&project_id).total_deposited, 250_000);
+
+    // A commitment can only be revealed once
+    let result = client.try_reveal_deposit(&user, &project_id, &salt);
+    assert_eq!(result, Err(Ok(CrowdfundError::CommitmentNotFound)));
+}
+
+#[test]
+fn test_reveal_deposit_wrong_salt_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    let salt = BytesN::from_array(&env, &[7u8; 32]);
+    let commitment = deposit_commitment(&env, project_id, &salt);
+    client.commit_deposit(&user, &commitment, &token_client.address, &250_000);
+
+    // Revealing with a different salt does not match the commitment
+    let wrong_salt = BytesN::from_array(&env, &[8u8; 32]);
+    let result = client.try_reveal_deposit(&user, &project_id, &wrong_salt);
+    assert_eq!(result, Err(Ok(CrowdfundError::CommitmentNotFound)));
+
+    // Nothing was attributed to the project
+    assert_eq!(client.get_balance(&project_id), 0);
+    assert_eq!(client.get_contribution(&project_id, &user), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #11)")]
+fn test_create_project_pause() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    let _ = client.pause(&admin);
+
+    // Create project
+    let _project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+}
+
+#[test]
+fn test_create_project_pause_unpause() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    let _ = client.pause(&admin);
+
+    let is_pause = client.require_not_paused();
+    assert!(is_pause);
+
+    let _ = client.unpause(&admin);
+
+    let is_pause = client.require_not_paused();
+    assert!(!is_pause);
+
+    // Create project
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
-    let id2 = client.create_project(
-        &owner2,
-        &symbol_short!("P2"),
-        &200_000,
+
+    assert_eq!(project_id, 0);
+
+    // Verify project data
+    let project = client.get_project(&project_id);
+    assert_eq!(project.id, 0);
+    assert_eq!(project.owner, owner);
+    assert_eq!(project.target_amount, 1_000_000);
+    assert_eq!(project.total_deposited, 0);
+    assert_eq!(project.total_withdrawn, 0);
+    assert!(project.is_active);
+
+    let is_pause = client.require_not_paused();
+    assert!(!is_pause);
+}
+
+#[test]
+fn test_is_paused() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+    assert!(!client.is_paused());
+
+    client.pause(&admin);
+    assert!(client.is_paused());
+
+    client.unpause(&admin);
+    assert!(!client.is_paused());
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #11)")]
+fn test_deposit_pause() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    // Create project
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    let _ = client.pause(&admin);
+
+    // Deposit funds
+    let deposit_amount: i128 = 500_000;
+    client.deposit(&user, &project_id, &deposit_amount, &None);
+}
+
+#[test]
+fn test_deposit_pause_unpause() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &None);
+
+    // Create project
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+
+    let _ = client.pause(&admin);
+
+    let is_pause = client.require_not_paused();
+    assert!(is_pause);
+
+    let _ = client.unpause(&admin);
+
+    let is_pause = client.require_not_paused();
+    assert!(!is_pause);
+
+    // Deposit funds
+    let deposit_amount: i128 = 500_000;
+    client.deposit(&user, &project_id, &deposit_amount, &None);
+
+    // Verify balance
+    assert_eq!(client.get_balance(&project_id), deposit_amount);
+
+    // Verify project data updated
+    let project = client.get_project(&project_id);
+    assert_eq!(project.total_deposited, deposit_amount);
+}
+
+#[test]
+fn test_guardian_unpause() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    let guardian = Address::generate(&env);
+    client.initialize(&admin, &Some(guardian.clone()));
+
+    client.pause(&admin);
+    assert!(client.require_not_paused());
+
+    // Only the guardian may use the recovery path
+    let result = client.try_guardian_unpause(&owner);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+
+    client.guardian_unpause(&guardian);
+    assert!(!client.require_not_paused());
+
+    // The contract is usable again
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert_eq!(project_id, 0);
+
+    // The guardian has no other admin privileges
+    let result = client.try_approve_milestone(&guardian, &project_id, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+}
+
+#[test]
+fn test_guardian_unpause_without_guardian() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+    client.pause(&admin);
+
+    let result = client.try_guardian_unpause(&owner);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+}
+
+#[test]
+fn test_version() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _, _, _, _) = setup_test(&env);
+    assert_eq!(client.version(), 1);
+}
+
+// ---------------------------------------------------------------------------
+// Upgradeability tests
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_set_admin_transfers_role() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let new_admin = Address::generate(&env);
+    client.set_admin(&admin, &new_admin);
+
+    assert_eq!(
+        client.get_admin(),
+        new_admin,
+        "admin must be updated after set_admin"
+    );
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    assert_eq!(client.get_pending_admin(), None);
+    let result = client.try_accept_admin(&admin);
+    assert_eq!(result, Err(Ok(CrowdfundError::NoPendingAdmin)));
+
+    let new_admin = Address::generate(&env);
+    client.propose_admin(&admin, &new_admin);
+    assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+    assert_eq!(client.get_admin(), admin);
+
+    // Only the proposed address can accept
+    let result = client.try_accept_admin(&admin);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+
+    client.accept_admin(&new_admin);
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+}
+
+#[test]
+fn test_only_admin_can_upgrade() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let non_admin = Address::generate(&env);
+    let dummy = soroban_sdk::BytesN::from_array(&env, &[0u8; 32]);
+
+    let result = client.try_upgrade(&non_admin, &dummy);
+    assert_eq!(result, Err(Ok(crate::errors::CrowdfundError::Unauthorized)));
+}
+
+#[test]
+fn test_old_admin_cannot_upgrade_after_rotation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _, _, _) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let new_admin = Address::generate(&env);
+    client.set_admin(&admin, &new_admin);
+
+    let dummy = soroban_sdk::BytesN::from_array(&env, &[0u8; 32]);
+    let result = client.try_upgrade(&admin, &dummy);
+    assert_eq!(result, Err(Ok(crate::errors::CrowdfundError::Unauthorized)));
+}
+
+fn assert_balance_invariant(client: &CrowdfundVaultContractClient, project_id: u64) {
+    let project = client.get_project(&project_id);
+    assert_eq!(
+        client.get_balance(&project_id),
+        project.total_deposited - project.total_withdrawn
+    );
+}
+
+#[test]
+fn test_balance_matches_deposits_minus_withdrawals() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert_balance_invariant(&client, project_id);
+
+    // Deposits
+    let user2 = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&user2, &1_000_000);
+    client.deposit(&user, &project_id, &400_000, &None);
+    client.deposit(&user2, &project_id, &250_000, &None);
+    assert_balance_invariant(&client, project_id);
+
+    // Matching funds
+    StellarAssetClient::new(&env, &token_client.address).mint(&admin, &10_000_000);
+    client.contribute_to_matching_pool(&admin, &token_client.address, &10_000_000);
+    assert!(client.distribute_match(&admin, &project_id) > 0);
+    assert_balance_invariant(&client, project_id);
+
+    // Withdrawals
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+    let balance = client.get_balance(&project_id);
+    client.withdraw(&project_id, &(balance - 50));
+    assert_balance_invariant(&client, project_id);
+
+    // Sweeping dust after close
+    client.close_project(&admin, &project_id);
+    client.sweep_dust(&admin, &project_id, &Address::generate(&env));
+    assert_eq!(client.get_balance(&project_id), 0);
+    assert_balance_invariant(&client, project_id);
+}
+
+#[test]
+fn test_creation_enabled_flag() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let existing = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert!(client.is_creation_enabled());
+
+    // Only the admin may toggle the flag
+    let result = client.try_set_creation_enabled(&owner, &false);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+
+    client.set_creation_enabled(&admin, &false);
+    assert!(!client.is_creation_enabled());
+
+    let result = client.try_create_project(
+        &owner,
+        &symbol_short!("Blocked"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectCreationDisabled)));
+
+    // Existing projects keep working
+    client.deposit(&user, &existing, &1_000, &None);
+    assert_eq!(client.get_balance(&existing), 1_000);
+
+    client.set_creation_enabled(&admin, &true);
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("Allowed"),
+        &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
-    let id3 = client.create_project(
-        &owner3,
-        &symbol_short!("P3"),
-        &300_000,
+    assert_eq!(project_id, existing + 1);
+}
+
+#[test]
+fn test_get_deposit_history() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
+    assert_eq!(client.get_deposit_history(&project_id, &user).len(), 0);
 
-    assert_eq!(id1, 0);
-    assert_eq!(id2, 1);
-    assert_eq!(id3, 2);
+    env.ledger().set_timestamp(1_000);
+    client.deposit(&user, &project_id, &100, &None);
+    env.ledger().set_timestamp(2_000);
+    client.deposit(&user, &project_id, &250, &None);
+    env.ledger().set_timestamp(3_000);
+    client.deposit(&user, &project_id, &50, &None);
 
-    // Verify all projects exist with correct data
-    assert_eq!(client.get_project(&id1).target_amount, 100_000);
-    assert_eq!(client.get_project(&id2).target_amount, 200_000);
-    assert_eq!(client.get_project(&id3).target_amount, 300_000);
+    let history = client.get_deposit_history(&project_id, &user);
+    assert_eq!(
+        history,
+        Vec::from_array(&env, [(100i128, 1_000u64), (250, 2_000), (50, 3_000)])
+    );
+    assert_eq!(client.get_contribution(&project_id, &user), 400);
 
-    // Verify next project ID is 3
-    // This is tested implicitly through sequential creation
+    let result = client.try_get_deposit_history(&99, &user);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
 }
 
 #[test]
-fn test_fund_matching_pool_unauthorized() {
+fn test_batch_claim_refund() {
     let env = Env::default();
     env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
 
-    let (client, admin, owner, _, token_client) = setup_test(&env);
-
-    // Initialize contract
-    client.initialize(&admin);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let mut ids = Vec::new(&env);
+    for name in [
+        symbol_short!("Expired"),
+        symbol_short!("Closed"),
+        symbol_short!("Funded"),
+    ] {
+        ids.push_back(client.create_project(
+            &owner,
+            &name,
+            &1_000_000,
+            &token_client.address,
+            &None,
+            &symbol_short!("General"),
+        ));
+    }
+    let (expired, closed, funded) = (
+        ids.get(0).unwrap(),
+        ids.get(1).unwrap(),
+        ids.get(2).unwrap(),
+    );
 
-    // Non-admin tries to fund matching pool - should fail
-    let result = client.try_fund_matching_pool(&owner, &token_client.address, &10_000_000);
-    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+    client.set_deadline(&expired, &2_000);
+    client.set_deadline(&funded, &2_000);
+    client.deposit(&user, &expired, &300_000, &None);
+    client.deposit(&user, &closed, &200_000, &None);
+    client.deposit(&user, &funded, &1_000_000, &None);
+    client.close_project(&admin, &closed);
+
+    // Nothing is refundable before the deadline, but the closed project is
+    let result = client.try_claim_refund(&user, &expired);
+    assert_eq!(result, Err(Ok(CrowdfundError::NotRefundable)));
+
+    env.ledger().set_timestamp(2_001);
+    let before = token_client.balance(&user);
+    assert_eq!(client.batch_claim_refund(&user, &ids), 500_000);
+    assert_eq!(token_client.balance(&user), before + 500_000);
+
+    assert_eq!(client.get_balance(&expired), 0);
+    assert_eq!(client.get_balance(&closed), 0);
+    assert_eq!(client.get_balance(&funded), 1_000_000);
+    assert_eq!(client.get_contribution(&funded, &user), 1_000_000);
+
+    // Refunds are paid once
+    assert_eq!(client.batch_claim_refund(&user, &ids), 0);
+    let result = client.try_claim_refund(&user, &closed);
+    assert_eq!(result, Err(Ok(CrowdfundError::NotRefundable)));
 }
 
 #[test]
-fn test_calculate_match_single_contributor() {
+fn test_deadline_blocks_redeposit_and_owner_withdrawal() {
     let env = Env::default();
     env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Initialize contract
-    client.initialize(&admin);
-
-    // Create project
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
+    client.set_deadline(&project_id, &2_000);
+    client.deposit(&user, &project_id, &300_000, &None);
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
 
-    // Deposit funds from single contributor
-    let contribution: i128 = 1_000_000; // 1M tokens
-    client.deposit(&user, &project_id, &contribution);
-
-    // Calculate match
-    // sqrt(1_000_000) = 1000
-    // match = 1000^2 = 1_000_000
-    let match_amount = client.calculate_match(&project_id);
-    assert!(match_amount > 0);
+    // Once the underfunded project misses its deadline the owner can't drain it
+    env.ledger().set_timestamp(2_001);
+    let result = client.try_withdraw(&project_id, &100_000);
+    assert_eq!(result, Err(Ok(CrowdfundError::DeadlinePassed)));
 
-    // Verify contributor count
+    // A refunded contributor can't deposit again and take a second index slot
+    assert_eq!(client.claim_refund(&user, &project_id), 300_000);
+    let result = client.try_deposit(&user, &project_id, &100_000, &None);
+    assert_eq!(result, Err(Ok(CrowdfundError::DeadlinePassed)));
     assert_eq!(client.get_contributor_count(&project_id), 1);
-
-    // Verify contribution amount
-    assert_eq!(client.get_contribution(&project_id, &user), contribution);
 }
 
 #[test]
-fn test_calculate_match_multiple_contributors() {
+fn test_withdraw_is_limited_per_token() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, owner, _, token_client) = setup_test(&env);
-
-    // Initialize contract
-    client.initialize(&admin);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Create project
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
+    let (other_token, other_token_admin) = create_token_contract(&env, &admin);
+    other_token_admin.mint(&user, &50_000);
 
-    // Create multiple users
-    let user1 = Address::generate(&env);
+    // Project-token deposits plus a match, and a smaller secondary-token deposit
     let user2 = Address::generate(&env);
-    let user3 = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&user2, &1_000_000);
+    StellarAssetClient::new(&env, &token_client.address).mint(&admin, &10_000_000);
+    client.deposit(&user, &project_id, &40_000, &None);
+    client.deposit(&user2, &project_id, &25_000, &None);
+    client.contribute_to_matching_pool(&admin, &token_client.address, &10_000_000);
+    let matched = client.distribute_match(&admin, &project_id);
+    assert!(matched > 0);
+    client.deposit_token(&user, &project_id, &other_token.address, &20_000);
+
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+
+    // The matched project-token balance doesn't back secondary-token withdrawals
+    let result = client.try_withdraw_token(&project_id, &other_token.address, &20_001);
+    assert_eq!(result, Err(Ok(CrowdfundError::InsufficientBalance)));
 
-    // Mint tokens to users
-    let (_, token_admin_client) = create_token_contract(&env, &admin);
-    token_admin_client.mint(&user1, &10_000_000);
-    token_admin_client.mint(&user2, &10_000_000);
-    token_admin_client.mint(&user3, &10_000_000);
+    // Nor does the secondary balance back project-token withdrawals
+    let project_balance = client.get_balance(&project_id);
+    assert_eq!(project_balance, 65_000 + matched);
+    let result = client.try_withdraw(&project_id, &(project_balance + 1));
+    assert_eq!(result, Err(Ok(CrowdfundError::InsufficientBalance)));
 
-    // Different contributions
-    // user1: 100 (sqrt = 10)
-    // user2: 400 (sqrt = 20)
-    // user3: 900 (sqrt = 30)
-    // sum of sqrt = 60
-    // match = 60^2 = 3600
-    client.deposit(&user1, &project_id, &100);
-    client.deposit(&user2, &project_id, &400);
-    client.deposit(&user3, &project_id, &900);
+    client.withdraw_token(&project_id, &other_token.address, &20_000);
+    client.withdraw(&project_id, &project_balance);
+    assert_eq!(other_token.balance(&owner), 20_000);
+    assert_eq!(token_client.balance(&owner), project_balance);
+}
 
-    // Calculate match
-    let match_amount = client.calculate_match(&project_id);
+#[test]
+fn test_set_project_token_rejects_held_secondary_token() {
+    let env = Env::default();
+    env.mock_all_auths();
 
-    // Verify match is approximately 3600 (allowing for fixed-point rounding)
-    // sqrt(100) ≈ 10, sqrt(400) = 20, sqrt(900) = 30
-    // sum = 60, match = 3600
-    assert!((3500..=3700).contains(&match_amount));
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Verify contributor count
-    assert_eq!(client.get_contributor_count(&project_id), 3);
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let (other_token, other_token_admin) = create_token_contract(&env, &admin);
+    other_token_admin.mint(&user, &50_000);
+    client.deposit_token(&user, &project_id, &other_token.address, &20_000);
+
+    // Adopting the held token would turn secondary deposits into project funds
+    let result = client.try_set_project_token(&project_id, &other_token.address);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectHasDeposits)));
+    assert_eq!(
+        client.get_balance_for_token(&project_id, &other_token.address),
+        20_000
+    );
 }
 
 #[test]
-fn test_calculate_match_no_contributors() {
+fn test_minimal_events_mode() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, owner, _, token_client) = setup_test(&env);
-
-    // Initialize contract
-    client.initialize(&admin);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+    assert!(!client.is_minimal_events());
 
-    // Create project
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
-        &1_000_000,
+        &1_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
-    // Calculate match with no contributors
-    let match_amount = client.calculate_match(&project_id);
-    assert_eq!(match_amount, 0);
+    // Full mode emits informational events
+    client.register_contributor(&user);
+    assert_eq!(count_events(&env, "contributor_registered_event"), 1);
+
+    client.set_minimal_events(&admin, &true);
+    assert!(client.is_minimal_events());
+    let seq = client.get_event_seq();
+
+    // Informational events are suppressed and don't consume a seq
+    client.update_reputation(&admin, &user, &10);
+    assert_eq!(count_events(&env, "reputation_updated_event"), 0);
+    assert_eq!(client.get_event_seq(), seq);
+
+    // Critical events are still emitted; reaching the target is not
+    client.deposit(&user, &project_id, &1_000, &None);
+    assert_eq!(count_events(&env, "deposit_event"), 1);
+    assert_eq!(count_events(&env, "target_reached_event"), 0);
+    assert_eq!(last_event_seq(&env), seq + 1);
+
+    client.set_minimal_events(&admin, &false);
+    client.update_reputation(&admin, &user, &20);
+    assert_eq!(count_events(&env, "reputation_updated_event"), 1);
 }
 
 #[test]
-fn test_distribute_match() {
+fn test_free_matching_pool_excludes_reservations() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Initialize contract
-    client.initialize(&admin);
+    let reserved_project = client.create_project(
+        &owner,
+        &symbol_short!("Reserved"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let other_project = client.create_project(
+        &owner,
+        &symbol_short!("Other"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &reserved_project, &10_000, &None);
+    client.deposit(&user, &other_project, &10_000, &None);
+
+    StellarAssetClient::new(&env, &token_client.address).mint(&admin, &12_000);
+    client.contribute_to_matching_pool(&admin, &token_client.address, &12_000);
+    assert_eq!(client.get_free_matching_pool(&token_client.address), 12_000);
+
+    client.reserve_match(&admin, &reserved_project, &5_000);
+    assert_eq!(client.get_reserved_match(&reserved_project), 5_000);
+    assert_eq!(client.get_free_matching_pool(&token_client.address), 7_000);
+    assert_eq!(
+        client.get_matching_pool_balance(&token_client.address),
+        12_000
+    );
+
+    // Reservations must fit within the free pool
+    let result = client.try_reserve_match(&admin, &other_project, &7_001);
+    assert_eq!(result, Err(Ok(CrowdfundError::InsufficientBalance)));
+
+    // Other projects can only draw on the free pool
+    assert_eq!(client.calculate_match(&other_project), 10_000);
+    assert_eq!(client.distribute_match(&admin, &other_project), 7_000);
+    assert_eq!(client.get_free_matching_pool(&token_client.address), 0);
+    assert_eq!(
+        client.get_matching_pool_balance(&token_client.address),
+        5_000
+    );
+
+    // The reserved project consumes its reservation
+    assert_eq!(client.distribute_match(&admin, &reserved_project), 5_000);
+    assert_eq!(client.get_reserved_match(&reserved_project), 0);
+    assert_eq!(client.get_matching_pool_balance(&token_client.address), 0);
+    assert_eq!(client.get_free_matching_pool(&token_client.address), 0);
+}
+
+#[test]
+fn test_project_allowlist() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Create project
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
+    let outsider = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&outsider, &10_000);
+
+    // Open by default
+    assert!(!client.is_allowlist_enabled(&project_id));
+    client.deposit(&outsider, &project_id, &1_000, &None);
+
+    client.set_allowlist_enabled(&project_id, &true);
+    client.set_allowed(&project_id, &user, &true);
+    assert!(client.is_allowlisted(&project_id, &user));
+    assert!(!client.is_allowlisted(&project_id, &outsider));
+
+    client.deposit(&user, &project_id, &2_000, &None);
+    let result = client.try_deposit(&outsider, &project_id, &1_000, &None);
+    assert_eq!(result, Err(Ok(CrowdfundError::NotAllowlisted)));
+
+    // Removed contributors are rejected too
+    client.set_allowed(&project_id, &user, &false);
+    let result = client.try_deposit(&user, &project_id, &1_000, &None);
+    assert_eq!(result, Err(Ok(CrowdfundError::NotAllowlisted)));
+
+    // Disabling the allowlist reopens the project
+    client.set_allowlist_enabled(&project_id, &false);
+    client.deposit(&outsider, &project_id, &1_000, &None);
+    assert_eq!(client.get_balance(&project_id), 4_000);
+}
 
-    // Deposit funds
-    let contribution: i128 = 1_000_000;
-    client.deposit(&user, &project_id, &contribution);
+#[test]
+fn test_dedupe_contributors() {
+    let env = Env::default();
+    env.mock_all_auths();
 
-    // Fund matching pool
-    let pool_amount: i128 = 10_000_000;
-    let (_, token_admin_client) = create_token_contract(&env, &admin);
-    token_admin_client.mint(&admin, &pool_amount);
-    client.fund_matching_pool(&admin, &token_client.address, &pool_amount);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Get initial balance
-    let initial_balance = client.get_balance(&project_id);
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let user2 = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&user2, &10_000);
+    client.deposit(&user, &project_id, &4_000, &None);
+    client.deposit(&user2, &project_id, &9_000, &None);
+    let expected_match = client.calculate_match(&project_id);
+
+    // Nothing to merge in a healthy index
+    assert_eq!(client.dedupe_contributors(&admin, &project_id), 0);
+
+    // Simulate a bug that indexed `user` a second time, ahead of `user2`
+    env.as_contract(&client.address, || {
+        use crate::storage::DataKey;
+        let storage = env.storage().persistent();
+        storage.set(&DataKey::Contributor(project_id, 1), &user);
+        storage.set(&DataKey::Contributor(project_id, 2), &user2);
+        storage.set(&DataKey::ContributorCount(project_id), &3u32);
+    });
+    assert_eq!(client.get_contributor_count(&project_id), 3);
 
-    // Calculate and distribute match
-    let match_amount = client.calculate_match(&project_id);
-    let distributed = client.distribute_match(&project_id);
+    let result = client.try_dedupe_contributors(&owner, &project_id);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
 
-    // Verify match was distributed
-    assert!(distributed > 0);
-    assert_eq!(distributed, match_amount);
+    assert_eq!(client.dedupe_contributors(&admin, &project_id), 1);
+    assert_eq!(client.get_contributor_count(&project_id), 2);
+    env.as_contract(&client.address, || {
+        use crate::storage::DataKey;
+        let storage = env.storage().persistent();
+        assert_eq!(
+            storage.get::<_, Address>(&DataKey::Contributor(project_id, 0)),
+            Some(user.clone())
+        );
+        assert_eq!(
+            storage.get::<_, Address>(&DataKey::Contributor(project_id, 1)),
+            Some(user2.clone())
+        );
+        assert!(!storage.has(&DataKey::Contributor(project_id, 2)));
+    });
+
+    // Contributions are untouched and the match is back to its true value
+    assert_eq!(client.get_contribution(&project_id, &user), 4_000);
+    assert_eq!(client.calculate_match(&project_id), expected_match);
+    let (stored, recomputed) = sum_sqrt_pair(&env, &client, project_id);
+    assert_eq!(stored, recomputed);
+}
 
-    // Verify project balance increased
-    let new_balance = client.get_balance(&project_id);
-    assert_eq!(new_balance, initial_balance + distributed);
+#[test]
+fn test_get_withdraw_history() {
+    let env = Env::default();
+    env.mock_all_auths();
 
-    // Verify matching pool decreased
-    let remaining_pool = client.get_matching_pool_balance(&token_client.address);
-    assert_eq!(remaining_pool, pool_amount - distributed);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &500_000, &None);
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+    assert_eq!(client.get_withdraw_history(&project_id).len(), 0);
+
+    // An owner withdrawal, then the leftover dust swept to a treasury
+    env.ledger().set_timestamp(1_000);
+    client.withdraw(&project_id, &499_900);
+    client.close_project(&admin, &project_id);
+    let treasury = Address::generate(&env);
+    env.ledger().set_timestamp(2_000);
+    client.sweep_dust(&admin, &project_id, &treasury);
+
+    assert_eq!(
+        client.get_withdraw_history(&project_id),
+        vec![
+            &env,
+            (499_900i128, 1_000u64, owner.clone()),
+            (100, 2_000, treasury.clone())
+        ]
+    );
+    assert_eq!(client.get_project(&project_id).total_withdrawn, 500_000);
 }
 
 #[test]
-fn test_contributor_registration() {
+fn test_get_balance_breakdown() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, _, user, _) = setup_test(&env);
-    client.initialize(&admin);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Register contributor
-    client.register_contributor(&user);
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert_eq!(client.get_balance_breakdown(&project_id), (0, 0, 0));
 
-    // Verify reputation is 0
-    assert_eq!(client.get_reputation(&user), 0);
+    let user2 = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&user2, &10_000);
+    client.deposit(&user, &project_id, &400, &None);
+    client.deposit(&user2, &project_id, &900, &None);
+    assert_eq!(client.get_balance_breakdown(&project_id), (1_300, 0, 0));
+
+    StellarAssetClient::new(&env, &token_client.address).mint(&admin, &100_000);
+    client.contribute_to_matching_pool(&admin, &token_client.address, &100_000);
+    let matched = client.distribute_match(&admin, &project_id);
+    assert_eq!(matched, 2_500);
+    assert_eq!(client.get_balance_breakdown(&project_id), (1_300, 2_500, 0));
+
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+    client.withdraw(&project_id, &2_000);
+    let (deposited, matched, withdrawn) = client.get_balance_breakdown(&project_id);
+    assert_eq!((deposited, matched, withdrawn), (1_300, 2_500, 2_000));
+    assert_eq!(
+        client.get_balance(&project_id),
+        deposited + matched - withdrawn
+    );
 
-    // Try to register again - should fail
-    let result = client.try_register_contributor(&user);
-    assert_eq!(result, Err(Ok(CrowdfundError::AlreadyRegistered)));
+    let result = client.try_get_balance_breakdown(&99);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
 }
 
 #[test]
-fn test_reputation_management() {
+fn test_get_active_project_count() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, _, user, _) = setup_test(&env);
-    client.initialize(&admin);
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+    assert_eq!(client.get_active_project_count(), 0);
+
+    let mut ids = Vec::new(&env);
+    for name in [
+        symbol_short!("ProjA"),
+        symbol_short!("ProjB"),
+        symbol_short!("ProjC"),
+    ] {
+        ids.push_back(client.create_project(
+            &owner,
+            &name,
+            &1_000_000,
+            &token_client.address,
+            &None,
+            &symbol_short!("General"),
+        ));
+    }
+    assert_eq!(client.get_active_project_count(), 3);
+
+    client.close_project(&admin, &ids.get(1).unwrap());
+    assert_eq!(client.get_active_project_count(), 2);
+    assert_eq!(client.get_project_count(), 3);
+
+    // Closing twice fails and doesn't change the count
+    let result = client.try_close_project(&admin, &ids.get(1).unwrap());
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotActive)));
+    assert_eq!(client.get_active_project_count(), 2);
+}
 
-    // Register contributor first
-    client.register_contributor(&user);
+#[test]
+fn test_match_in_separate_matching_token() {
+    let env = Env::default();
+    env.mock_all_auths();
 
-    // Update reputation
-    client.update_reputation(&admin, &user, &100);
-    assert_eq!(client.get_reputation(&user), 100);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Decrease reputation
-    client.update_reputation(&admin, &user, &-50);
-    assert_eq!(client.get_reputation(&user), 50);
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert_eq!(client.get_matching_token(&project_id), token_client.address);
+
+    let (match_token, match_token_admin) = create_token_contract(&env, &admin);
+    client.set_matching_token(&admin, &project_id, &Some(match_token.address.clone()));
+    assert_eq!(client.get_matching_token(&project_id), match_token.address);
+
+    let user2 = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&user2, &10_000);
+    client.deposit(&user, &project_id, &400, &None);
+    client.deposit(&user2, &project_id, &900, &None);
+
+    // The project token pool cannot pay a match owed in the matching token
+    StellarAssetClient::new(&env, &token_client.address).mint(&admin, &100_000);
+    client.contribute_to_matching_pool(&admin, &token_client.address, &100_000);
+    match_token_admin.mint(&admin, &100_000);
+    client.contribute_to_matching_pool(&admin, &match_token.address, &100_000);
+
+    let matched = client.distribute_match(&admin, &project_id);
+    assert_eq!(matched, 2_500);
+    assert_eq!(client.get_balance(&project_id), 1_300);
+    assert_eq!(
+        client.get_balance_for_token(&project_id, &match_token.address),
+        2_500
+    );
+    assert_eq!(
+        client.get_matching_pool_balance(&match_token.address),
+        97_500
+    );
+    assert_eq!(
+        client.get_matching_pool_balance(&token_client.address),
+        100_000
+    );
+
+    // Matches in the matching token still count as matched, tracked per token
+    assert_eq!(client.get_total_matched(&project_id), 2_500);
+    assert_eq!(
+        client.get_total_matched_in_token(&project_id, &match_token.address),
+        2_500
+    );
+    assert_eq!(
+        client.get_total_matched_in_token(&project_id, &token_client.address),
+        0
+    );
+    assert_eq!(client.get_balance_breakdown(&project_id), (1_300, 2_500, 0));
+
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+    client.withdraw(&project_id, &1_300);
+    client.withdraw_token(&project_id, &match_token.address, &2_500);
+    assert_eq!(token_client.balance(&owner), 1_300);
+    assert_eq!(match_token.balance(&owner), 2_500);
 
-    // Non-admin cannot update reputation
-    let non_admin = Address::generate(&env);
-    let result = client.try_update_reputation(&non_admin, &user, &100);
+    // Resetting goes back to matching in the project token
+    client.set_matching_token(&admin, &project_id, &None);
+    assert_eq!(client.get_matching_token(&project_id), token_client.address);
+
+    let result = client.try_set_matching_token(&owner, &project_id, &None);
     assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
+
+    // Matches are paid as the same amount, so the decimals must agree
+    let token_18 = env.register(MockToken18, ());
+    let result = client.try_set_matching_token(&admin, &project_id, &Some(token_18));
+    assert_eq!(result, Err(Ok(CrowdfundError::DecimalsMismatch)));
 }
 
 #[test]
-fn test_events_emission() {
+fn test_contribution_increased_event_on_top_up() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, owner, _user, token_client) = setup_test(&env);
-
-    // Initialize contract
-    client.initialize(&admin);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Create project
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
-    // Deposit funds from multiple users to create large match
-    let user1 = Address::generate(&env);
-    let user2 = Address::generate(&env);
-    let (_, token_admin_client) = create_token_contract(&env, &admin);
-    token_admin_client.mint(&user1, &10_000_000);
-    token_admin_client.mint(&user2, &10_000_000);
-
-    // Large contributions that will create a large match
-    client.deposit(&user1, &project_id, &1_000_000);
-    client.deposit(&user2, &project_id, &1_000_000);
-
-    // Fund matching pool with small amount
-    let pool_amount: i128 = 100_000; // Less than the calculated match
-    token_admin_client.mint(&admin, &pool_amount);
-    client.fund_matching_pool(&admin, &token_client.address, &pool_amount);
-
-    // Calculate match (should be large)
-    let match_amount = client.calculate_match(&project_id);
-    assert!(match_amount > pool_amount);
+    client.deposit(&user, &project_id, &100, &None);
+    assert_eq!(count_events(&env, "deposit_event"), 1);
+    assert_eq!(count_events(&env, "contribution_increased_event"), 0);
+
+    client.deposit(&user, &project_id, &250, &None);
+    assert_eq!(count_events(&env, "deposit_event"), 1);
+    assert_eq!(count_events(&env, "contribution_increased_event"), 1);
+
+    let name = Symbol::new(&env, "contribution_increased_event");
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .find(|(_, topics, _)| {
+            topics
+                .get(0)
+                .and_then(|topic| Symbol::try_from_val(&env, &topic).ok())
+                == Some(name.clone())
+        })
+        .unwrap();
+    let data = Map::<Symbol, Val>::try_from_val(&env, &data).unwrap();
+    let added = i128::try_from_val(&env, &data.get(Symbol::new(&env, "added")).unwrap()).unwrap();
+    let new_total =
+        i128::try_from_val(&env, &data.get(Symbol::new(&env, "new_total")).unwrap()).unwrap();
+    assert_eq!((added, new_total), (250, 350));
+}
 
-    // Distribute match (should only distribute what's available)
-    let distributed = client.distribute_match(&project_id);
+#[test]
+fn test_create_project_rejects_empty_name() {
+    let env = Env::default();
+    env.mock_all_auths();
 
-    // Should only distribute the pool amount, not the full match
-    assert_eq!(distributed, pool_amount);
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Verify pool is empty
-    assert_eq!(client.get_matching_pool_balance(&token_client.address), 0);
+    let result = client.try_create_project(
+        &owner,
+        &Symbol::new(&env, ""),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert_eq!(result, Err(Ok(CrowdfundError::InvalidName)));
+    assert_eq!(client.get_project_count(), 0);
 }
 
 #[test]
-fn test_multiple_contributions_same_user() {
+fn test_deposit_idempotency_key() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Initialize contract
-    client.initialize(&admin);
-
-    // Create project
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
-    // Same user makes multiple contributions
-    client.deposit(&user, &project_id, &100);
-    client.deposit(&user, &project_id, &300); // Total: 400
+    let key = BytesN::from_array(&env, &[7; 32]);
+    client.deposit(&user, &project_id, &500, &Some(key.clone()));
+    client.deposit(&user, &project_id, &500, &Some(key.clone()));
+    assert_eq!(client.get_balance(&project_id), 500);
+    assert_eq!(client.get_contribution(&project_id, &user), 500);
 
-    // Should only count as one contributor
-    assert_eq!(client.get_contributor_count(&project_id), 1);
+    // The key is scoped to the contributor
+    let user2 = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&user2, &10_000);
+    client.deposit(&user2, &project_id, &500, &Some(key));
+    assert_eq!(client.get_balance(&project_id), 1_000);
+
+    // A fresh key credits again
+    let key2 = BytesN::from_array(&env, &[8; 32]);
+    client.deposit(&user, &project_id, &500, &Some(key2));
+    assert_eq!(client.get_contribution(&project_id, &user), 1_000);
+}
 
-    // Total contribution should be 400
-    assert_eq!(client.get_contribution(&project_id, &user), 400);
+#[test]
+fn test_deposit_idempotency_key_mismatch_and_failed_attempts() {
+    let env = Env::default();
+    env.mock_all_auths();
 
-    // Calculate match: sqrt(400) = 20, match = 20^2 = 400
-    let match_amount = client.calculate_match(&project_id);
-    // Should be approximately 400 (allowing for rounding)
-    assert!((390..=410).contains(&match_amount));
-    // Deposit
-    client.deposit(&user, &project_id, &500_000);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Register contributor
-    client.register_contributor(&user);
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
 
-    // Update reputation
-    client.update_reputation(&admin, &user, &10);
+    // A failed deposit does not record the key
+    let key = BytesN::from_array(&env, &[9; 32]);
+    let result = client.try_deposit(&user, &99, &500, &Some(key.clone()));
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
+    client.deposit(&user, &project_id, &500, &Some(key.clone()));
+    assert_eq!(client.get_contribution(&project_id, &user), 500);
 
-    // Verify events exist (at least one event should be present)
-    let events = env.events().all();
-    assert!(
-        !events.is_empty(),
-        "Expected at least one event to be emitted"
+    // Reusing the key with different arguments is rejected
+    let result = client.try_deposit(&user, &project_id, &700, &Some(key.clone()));
+    assert_eq!(result, Err(Ok(CrowdfundError::IdempotencyKeyMismatch)));
+    let project_id2 = client.create_project(
+        &owner,
+        &symbol_short!("Other"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
+    let result = client.try_deposit(&user, &project_id2, &500, &Some(key));
+    assert_eq!(result, Err(Ok(CrowdfundError::IdempotencyKeyMismatch)));
+    assert_eq!(client.get_contribution(&project_id, &user), 500);
+    assert_eq!(client.get_contribution(&project_id2, &user), 0);
 }
 
 #[test]
-fn test_fund_matching_pool() {
+fn test_is_milestone_approved_at() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, _, _, token_client) = setup_test(&env);
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Initialize contract
-    client.initialize(&admin);
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
 
-    // Fund matching pool
-    let pool_amount: i128 = 10_000_000;
-    client.fund_matching_pool(&admin, &token_client.address, &pool_amount);
+    client.submit_milestone(&project_id, &0);
+    client.submit_milestone(&project_id, &1);
+    client.approve_milestone(&admin, &project_id, &1);
 
-    // Verify matching pool balance
-    assert_eq!(
-        client.get_matching_pool_balance(&token_client.address),
-        pool_amount
-    );
+    assert!(!client.is_milestone_approved_at(&project_id, &0));
+    assert!(client.is_milestone_approved_at(&project_id, &1));
+    assert!(!client.is_milestone_approved_at(&project_id, &2));
+
+    let result = client.try_is_milestone_approved_at(&99, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotFound)));
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #11)")]
-fn test_create_project_pause() {
+fn test_deposit_fee_not_charged_on_unfunded_deposit() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, owner, _, token_client) = setup_test(&env);
-
-    // Initialize contract
-    client.initialize(&admin);
-
-    let _ = client.pause(&admin);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Create project
-    let _project_id = client.create_project(
+    let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
+    let collector = Address::generate(&env);
+    client.set_deposit_fee(&admin, &250, &collector);
+
+    // A funded deposit puts tokens in the vault and pays the fee
+    client.deposit(&user, &project_id, &10_000, &None);
+    assert_eq!(token_client.balance(&collector), 250);
+    assert_eq!(token_client.balance(&client.address), 9_750);
+
+    // A user without the tokens moves nothing, so no fee leaves the vault
+    let poor_user = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_client.address).mint(&poor_user, &100);
+    client.deposit(&poor_user, &project_id, &10_000, &None);
+    assert_eq!(token_client.balance(&collector), 250);
+    assert_eq!(token_client.balance(&client.address), 9_750);
+    assert_eq!(token_client.balance(&poor_user), 100);
 }
 
 #[test]
-fn test_create_project_pause_unpause() {
+fn test_reject_held_deposit() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, owner, _, token_client) = setup_test(&env);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Initialize contract
-    client.initialize(&admin);
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.set_large_deposit_threshold(&admin, &Some(10_000));
+    client.deposit(&user, &project_id, &10_000, &None);
+    assert_eq!(token_client.balance(&user), 10_000_000 - 10_000);
+
+    // The token can't change while a deposit is held in it
+    let (other_token, _) = create_token_contract(&env, &admin);
+    let result = client.try_set_project_token(&project_id, &other_token.address);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectHasDeposits)));
+
+    // Approval is blocked while paused and once the project is closed
+    client.pause(&admin);
+    let result = client.try_approve_deposit(&admin, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::ContractPaused)));
+    let result = client.try_reject_deposit(&admin, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::ContractPaused)));
+    client.unpause(&admin);
+
+    client.close_project(&admin, &project_id);
+    let result = client.try_approve_deposit(&admin, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::ProjectNotActive)));
+
+    let result = client.try_reject_deposit(&owner, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::Unauthorized)));
 
-    let _ = client.pause(&admin);
+    // Rejecting refunds the depositor
+    client.reject_deposit(&admin, &0);
+    assert_eq!(token_client.balance(&user), 10_000_000);
+    assert_eq!(token_client.balance(&client.address), 0);
+    assert_eq!(client.get_contribution(&project_id, &user), 0);
+    assert_eq!(
+        client.audit_solvency(),
+        vec![&env, (token_client.address.clone(), 0, 0)]
+    );
 
-    let is_pause = client.require_not_paused();
-    assert!(is_pause);
+    let result = client.try_reject_deposit(&admin, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::PendingDepositNotFound)));
+}
 
-    let _ = client.unpause(&admin);
+#[test]
+fn test_held_deposit_releases_token_change() {
+    let env = Env::default();
+    env.mock_all_auths();
 
-    let is_pause = client.require_not_paused();
-    assert!(!is_pause);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Create project
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
-
-    assert_eq!(project_id, 0);
-
-    // Verify project data
-    let project = client.get_project(&project_id);
-    assert_eq!(project.id, 0);
-    assert_eq!(project.owner, owner);
-    assert_eq!(project.target_amount, 1_000_000);
-    assert_eq!(project.total_deposited, 0);
-    assert_eq!(project.total_withdrawn, 0);
-    assert!(project.is_active);
-
-    let is_pause = client.require_not_paused();
-    assert!(!is_pause);
+    client.set_large_deposit_threshold(&admin, &Some(10_000));
+    client.deposit(&user, &project_id, &10_000, &None);
+    client.reject_deposit(&admin, &0);
+
+    // Once nothing is held the project may switch tokens again
+    let (other_token, _) = create_token_contract(&env, &admin);
+    client.set_project_token(&project_id, &other_token.address);
+    assert_eq!(client.get_project_token(&project_id), other_token.address);
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #11)")]
-fn test_deposit_pause() {
+fn test_reveal_deposit_is_held_above_threshold() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Initialize contract
-    client.initialize(&admin);
-
-    // Create project
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
+    client.set_large_deposit_threshold(&admin, &Some(10_000));
 
-    let _ = client.pause(&admin);
+    let salt = BytesN::from_array(&env, &[7u8; 32]);
+    let commitment = deposit_commitment(&env, project_id, &salt);
+    client.commit_deposit(&user, &commitment, &token_client.address, &250_000);
+    client.reveal_deposit(&user, &project_id, &salt);
 
-    // Deposit funds
-    let deposit_amount: i128 = 500_000;
-    client.deposit(&user, &project_id, &deposit_amount);
+    // The revealed funds wait for approval like a direct deposit
+    assert_eq!(client.get_balance(&project_id), 0);
+    assert_eq!(client.get_pending_deposit(&0).amount, 250_000);
+
+    client.approve_deposit(&admin, &0);
+    assert_eq!(client.get_balance(&project_id), 250_000);
+    assert_eq!(client.get_contribution(&project_id, &user), 250_000);
 }
 
 #[test]
-fn test_deposit_pause_unpause() {
+fn test_reveal_deposit_uses_deposit_checks() {
     let env = Env::default();
     env.mock_all_auths();
 
     let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Initialize contract
-    client.initialize(&admin);
-
-    // Create project
     let project_id = client.create_project(
         &owner,
         &symbol_short!("TestProj"),
         &1_000_000,
         &token_client.address,
+        &None,
+        &symbol_short!("General"),
     );
 
-    let _ = client.pause(&admin);
-
-    let is_pause = client.require_not_paused();
-    assert!(is_pause);
+    // A non-allowlisted user can't get around the allowlist by committing
+    client.set_allowlist_enabled(&project_id, &true);
+    let salt = BytesN::from_array(&env, &[7u8; 32]);
+    let commitment = deposit_commitment(&env, project_id, &salt);
+    client.commit_deposit(&user, &commitment, &token_client.address, &10_000);
+    let result = client.try_reveal_deposit(&user, &project_id, &salt);
+    assert_eq!(result, Err(Ok(CrowdfundError::NotAllowlisted)));
+
+    // Once allowed, the reveal pays the deposit fee
+    client.set_allowed(&project_id, &user, &true);
+    let collector = Address::generate(&env);
+    client.set_deposit_fee(&admin, &250, &collector);
+    client.set_deposit_cooldown(&project_id, &60);
+    client.reveal_deposit(&user, &project_id, &salt);
+    assert_eq!(token_client.balance(&collector), 250);
+    assert_eq!(client.get_contribution(&project_id, &user), 9_750);
+    assert_eq!(client.get_balance(&project_id), 9_750);
+
+    // And starts the deposit cooldown
+    let salt2 = BytesN::from_array(&env, &[8u8; 32]);
+    let commitment2 = deposit_commitment(&env, project_id, &salt2);
+    client.commit_deposit(&user, &commitment2, &token_client.address, &10_000);
+    let result = client.try_reveal_deposit(&user, &project_id, &salt2);
+    assert_eq!(result, Err(Ok(CrowdfundError::DepositCooldownActive)));
+}
 
-    let _ = client.unpause(&admin);
+#[test]
+fn test_close_project_releases_reservation() {
+    let env = Env::default();
+    env.mock_all_auths();
 
-    let is_pause = client.require_not_paused();
-    assert!(!is_pause);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    // Deposit funds
-    let deposit_amount: i128 = 500_000;
-    client.deposit(&user, &project_id, &deposit_amount);
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &project_id, &10_000, &None);
 
-    // Verify balance
-    assert_eq!(client.get_balance(&project_id), deposit_amount);
+    StellarAssetClient::new(&env, &token_client.address).mint(&admin, &12_000);
+    client.contribute_to_matching_pool(&admin, &token_client.address, &12_000);
+    client.reserve_match(&admin, &project_id, &5_000);
+    assert_eq!(client.get_free_matching_pool(&token_client.address), 7_000);
 
-    // Verify project data updated
-    let project = client.get_project(&project_id);
-    assert_eq!(project.total_deposited, deposit_amount);
+    client.close_project(&admin, &project_id);
+    assert_eq!(client.get_reserved_match(&project_id), 0);
+    assert_eq!(client.get_free_matching_pool(&token_client.address), 12_000);
 }
 
-// ---------------------------------------------------------------------------
-// Upgradeability tests
-// ---------------------------------------------------------------------------
-
 #[test]
-fn test_set_admin_transfers_role() {
+fn test_distribute_round_keeps_reservations() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, _, _, _) = setup_test(&env);
-    client.initialize(&admin);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    let new_admin = Address::generate(&env);
-    client.set_admin(&admin, &new_admin);
+    let reserved_project = client.create_project(
+        &owner,
+        &symbol_short!("Reserved"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    let other_project = client.create_project(
+        &owner,
+        &symbol_short!("Other"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.deposit(&user, &reserved_project, &10_000, &None);
+    client.deposit(&user, &other_project, &10_000, &None);
 
+    StellarAssetClient::new(&env, &token_client.address).mint(&admin, &12_000);
+    client.contribute_to_matching_pool(&admin, &token_client.address, &12_000);
+    client.reserve_match(&admin, &reserved_project, &5_000);
+
+    // The round pays both projects from the free pool only
+    let distributed = client.distribute_round(
+        &admin,
+        &token_client.address,
+        &vec![&env, reserved_project, other_project],
+    );
+    assert_eq!(distributed, vec![&env, 3_500, 3_500]);
+
+    // The reservation is still there for a later `distribute_match`
+    assert_eq!(client.get_reserved_match(&reserved_project), 5_000);
     assert_eq!(
-        client.get_admin(),
-        new_admin,
-        "admin must be updated after set_admin"
+        client.get_matching_pool_balance(&token_client.address),
+        5_000
     );
+    assert_eq!(client.get_free_matching_pool(&token_client.address), 0);
 }
 
 #[test]
-fn test_only_admin_can_upgrade() {
+fn test_reject_submitted_milestone_keeps_approval() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, _, _, _) = setup_test(&env);
-    client.initialize(&admin);
+    let (client, admin, owner, _, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
 
-    let non_admin = Address::generate(&env);
-    let dummy = soroban_sdk::BytesN::from_array(&env, &[0u8; 32]);
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    client.submit_milestone(&project_id, &0);
+    client.approve_milestone(&admin, &project_id, &0);
+    client.submit_milestone(&project_id, &1);
 
-    let result = client.try_upgrade(&non_admin, &dummy);
-    assert_eq!(result, Err(Ok(crate::errors::CrowdfundError::Unauthorized)));
+    // Rejecting a milestone that was only submitted keeps withdrawals open
+    client.reject_milestone(&admin, &project_id, &1);
+    assert!(client.is_milestone_approved(&project_id));
+
+    // Rejection is blocked while paused
+    client.pause(&admin);
+    let result = client.try_reject_milestone(&admin, &project_id, &0);
+    assert_eq!(result, Err(Ok(CrowdfundError::ContractPaused)));
+    client.unpause(&admin);
+
+    // Rejecting one approved milestone keeps them open while another is approved
+    client.submit_milestone(&project_id, &2);
+    client.approve_milestone(&admin, &project_id, &2);
+    client.reject_milestone(&admin, &project_id, &2);
+    assert!(client.is_milestone_approved(&project_id));
+
+    // Rejecting the last approved milestone closes them
+    client.reject_milestone(&admin, &project_id, &0);
+    assert!(!client.is_milestone_approved(&project_id));
 }
 
 #[test]
-fn test_old_admin_cannot_upgrade_after_rotation() {
+fn test_minimal_events_keeps_only_fund_movements() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, _, _, _) = setup_test(&env);
-    client.initialize(&admin);
+    let (client, admin, owner, user, token_client) = setup_test(&env);
+    client.initialize(&admin, &None);
+    client.set_minimal_events(&admin, &true);
+    assert_eq!(
+        event_names(&env, &client.address),
+        vec![&env, Symbol::new(&env, "event_mode_updated_event")]
+    );
 
-    let new_admin = Address::generate(&env);
-    client.set_admin(&admin, &new_admin);
+    // Project setup, configuration and milestones are silent
+    let project_id = client.create_project(
+        &owner,
+        &symbol_short!("TestProj"),
+        &1_000,
+        &token_client.address,
+        &None,
+        &symbol_short!("General"),
+    );
+    assert_eq!(event_names(&env, &client.address), vec![&env]);
+    client.set_deposit_cooldown(&project_id, &0);
+    assert_eq!(event_names(&env, &client.address), vec![&env]);
+    client.set_match_fee(&admin, &100, &admin);
+    assert_eq!(event_names(&env, &client.address), vec![&env]);
+
+    // A deposit that reaches the target and tops up only reports the deposit
+    client.deposit(&user, &project_id, &500, &None);
+    client.deposit(&user, &project_id, &500, &None);
+    assert_eq!(
+        event_names(&env, &client.address),
+        vec![&env, Symbol::new(&env, "deposit_event")]
+    );
 
-    let dummy = soroban_sdk::BytesN::from_array(&env, &[0u8; 32]);
-    let result = client.try_upgrade(&admin, &dummy);
-    assert_eq!(result, Err(Ok(crate::errors::CrowdfundError::Unauthorized)));
+    client.submit_milestone(&project_id, &0);
+    assert_eq!(event_names(&env, &client.address), vec![&env]);
+    client.approve_milestone(&admin, &project_id, &0);
+    assert_eq!(event_names(&env, &client.address), vec![&env]);
+
+    client.withdraw(&project_id, &400);
+    assert_eq!(
+        event_names(&env, &client.address),
+        vec![&env, Symbol::new(&env, "withdraw_event")]
+    );
+
+    client.pause(&admin);
+    assert_eq!(event_names(&env, &client.address), vec![&env]);
 }
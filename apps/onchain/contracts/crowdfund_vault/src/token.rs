@@ -1,9 +1,38 @@
+use crate::errors::CrowdfundError;
 use soroban_sdk::{Address, Env};
 
 /// Transfer tokens from one address to another
-pub fn transfer(env: &Env, token: &Address, from: &Address, to: &Address, amount: &i128) {
+///
+/// A failing token call (frozen account, insufficient balance, ...) is reported
+/// as `TokenTransferFailed` instead of trapping the whole invocation.
+pub fn transfer(
+    env: &Env,
+    token: &Address,
+    from: &Address,
+    to: &Address,
+    amount: &i128,
+) -> Result<(), CrowdfundError> {
     let token_client = soroban_sdk::token::Client::new(env, token);
-    token_client.transfer(from, to, amount);
+    match token_client.try_transfer(from, to, amount) {
+        Ok(Ok(())) => Ok(()),
+        _ => Err(CrowdfundError::TokenTransferFailed),
+    }
+}
+
+/// Transfer tokens on behalf of `from` using an allowance granted to `spender`
+pub fn transfer_from(
+    env: &Env,
+    token: &Address,
+    spender: &Address,
+    from: &Address,
+    to: &Address,
+    amount: &i128,
+) -> Result<(), CrowdfundError> {
+    let token_client = soroban_sdk::token::Client::new(env, token);
+    match token_client.try_transfer_from(spender, from, to, amount) {
+        Ok(Ok(())) => Ok(()),
+        _ => Err(CrowdfundError::TokenTransferFailed),
+    }
 }
 
 /// Get the balance of an address for a given token
@@ -12,3 +41,21 @@ pub fn balance(env: &Env, token: &Address, address: &Address) -> i128 {
     let token_client = soroban_sdk::token::Client::new(env, token);
     token_client.balance(address)
 }
+
+/// Mint new tokens to an address; the calling contract must be the token admin
+pub fn mint(env: &Env, token: &Address, to: &Address, amount: &i128) {
+    let token_client = soroban_sdk::token::StellarAssetClient::new(env, token);
+    token_client.mint(to, amount);
+}
+
+/// Get the number of decimals for a given token
+pub fn decimals(env: &Env, token: &Address) -> u32 {
+    let token_client = soroban_sdk::token::Client::new(env, token);
+    token_client.decimals()
+}
+
+/// Check that `token` is a contract answering the token interface
+pub fn is_token(env: &Env, token: &Address) -> bool {
+    let token_client = soroban_sdk::token::Client::new(env, token);
+    matches!(token_client.try_decimals(), Ok(Ok(_)))
+}
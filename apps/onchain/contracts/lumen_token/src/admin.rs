@@ -1,4 +1,4 @@
-use soroban_sdk::{Address, Env};
+use soroban_sdk::{Address, Env, Vec};
 
 pub fn has_administrator(e: &Env) -> bool {
     let key = DataKey::Admin;
@@ -15,8 +15,23 @@ pub fn write_administrator(e: &Env, id: &Address) {
     e.storage().instance().set(&key, id);
 }
 
+pub fn read_minters(e: &Env) -> Vec<Address> {
+    let key = DataKey::Minters;
+    e.storage().instance().get(&key).unwrap_or(Vec::new(e))
+}
+
+pub fn write_minters(e: &Env, minters: &Vec<Address>) {
+    let key = DataKey::Minters;
+    e.storage().instance().set(&key, minters);
+}
+
+pub fn is_minter(e: &Env, id: &Address) -> bool {
+    read_minters(e).contains(id)
+}
+
 #[derive(Clone)]
 #[soroban_sdk::contracttype]
 pub enum DataKey {
     Admin,
+    Minters,
 }
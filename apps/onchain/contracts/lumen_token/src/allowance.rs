@@ -1,9 +1,13 @@
-use soroban_sdk::{Address, Env};
+use crate::balance::{BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{Address, BytesN, Env, Vec};
 
 #[derive(Clone)]
 #[soroban_sdk::contracttype]
 pub enum DataKey {
     Allowance(AllowanceDataKey),
+    Spenders(Address),    // owner -> Vec<Address>
+    PermitKey(Address),   // owner -> BytesN<32> ed25519 public key
+    PermitNonce(Address), // owner -> u64
 }
 
 #[derive(Clone)]
@@ -35,32 +39,132 @@ pub fn write_allowance(
     amount: i128,
     expiration_ledger: u32,
 ) {
-    let key = DataKey::Allowance(AllowanceDataKey { from, spender });
-    e.storage().temporary().set(
-        &key,
-        &AllowanceValue {
-            amount,
-            expiration_ledger,
-        },
-    );
+    let key = DataKey::Allowance(AllowanceDataKey {
+        from: from.clone(),
+        spender: spender.clone(),
+    });
+    let was_live = is_live(e, &read_allowance(e, from.clone(), spender.clone()));
+    let allowance = AllowanceValue {
+        amount,
+        expiration_ledger,
+    };
+    let live = is_live(e, &allowance);
+    e.storage().temporary().set(&key, &allowance);
+
+    // Only an allowance being granted or cleared changes the spender list, so
+    // spending part of an allowance never touches it
+    if live != was_live {
+        write_spender(e, from, spender, live);
+    }
+}
+
+/// Whether an allowance still lets its spender spend anything.
+fn is_live(e: &Env, allowance: &AllowanceValue) -> bool {
+    allowance.amount > 0 && allowance.expiration_ledger >= e.ledger().sequence()
+}
+
+/// Add `spender` to the owner's spender list when its allowance is granted, or
+/// drop it when the allowance is cleared.
+///
+/// Granting also prunes spenders whose allowance has since expired. The list
+/// lives in persistent storage so it outlives the temporary allowance entries;
+/// its TTL is extended on every write, and readers must still check each
+/// allowance for expiry.
+fn write_spender(e: &Env, from: Address, spender: Address, active: bool) {
+    let key = DataKey::Spenders(from.clone());
+    let spenders: Vec<Address> = e.storage().persistent().get(&key).unwrap_or(Vec::new(e));
+    let mut kept = Vec::new(e);
+    for existing in spenders.iter() {
+        if existing == spender {
+            continue;
+        }
+        if !active || is_live(e, &read_allowance(e, from.clone(), existing.clone())) {
+            kept.push_back(existing);
+        }
+    }
+    if active {
+        kept.push_back(spender);
+    }
+
+    if kept.is_empty() {
+        e.storage().persistent().remove(&key);
+        return;
+    }
+    e.storage().persistent().set(&key, &kept);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+/// List the spenders holding a non-zero, unexpired allowance from `from`.
+pub fn read_allowances_of(e: &Env, from: Address) -> Vec<AllowanceDataKey> {
+    let spenders: Vec<Address> = e
+        .storage()
+        .persistent()
+        .get(&DataKey::Spenders(from.clone()))
+        .unwrap_or(Vec::new(e));
+    let mut active = Vec::new(e);
+    for spender in spenders.iter() {
+        if is_live(e, &read_allowance(e, from.clone(), spender.clone())) {
+            active.push_back(AllowanceDataKey {
+                from: from.clone(),
+                spender,
+            });
+        }
+    }
+    active
 }
 
 pub fn spend_allowance(e: &Env, from: Address, spender: Address, amount: i128) {
     let allowance = read_allowance(e, from.clone(), spender.clone());
-    if allowance.amount < amount {
-        panic!("insufficient allowance");
-    }
+    let remaining = match allowance.amount.checked_sub(amount) {
+        Some(remaining) if remaining >= 0 => remaining,
+        _ => panic!("insufficient allowance"),
+    };
     // If expiration_ledger is 0, it means no expiration? Or should we handle that?
     // Usually 0 means expired or not set.
     // Let's assume strict expiration.
     if allowance.expiration_ledger < e.ledger().sequence() {
         panic!("allowance expired");
     }
-    write_allowance(
-        e,
-        from,
-        spender,
-        allowance.amount - amount,
-        allowance.expiration_ledger,
+    write_allowance(e, from, spender, remaining, allowance.expiration_ledger);
+}
+
+pub fn read_permit_key(e: &Env, from: Address) -> Option<BytesN<32>> {
+    e.storage().persistent().get(&DataKey::PermitKey(from))
+}
+
+/// Store the permit key for `from` and keep it alive as long as a balance.
+pub fn write_permit_key(e: &Env, from: Address, public_key: &BytesN<32>) {
+    let key = DataKey::PermitKey(from);
+    e.storage().persistent().set(&key, public_key);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+/// Extend the TTL of `from`'s permit key, so a key in use doesn't expire.
+pub fn bump_permit_key(e: &Env, from: Address) {
+    e.storage().persistent().extend_ttl(
+        &DataKey::PermitKey(from),
+        BALANCE_LIFETIME_THRESHOLD,
+        BALANCE_BUMP_AMOUNT,
     );
 }
+
+pub fn read_permit_nonce(e: &Env, from: Address) -> u64 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::PermitNonce(from))
+        .unwrap_or(0)
+}
+
+/// Store the next permit nonce for `from`, extending its TTL so an expired entry
+/// can never reset the nonce and replay old permits.
+pub fn write_permit_nonce(e: &Env, from: Address, nonce: u64) {
+    let key = DataKey::PermitNonce(from);
+    e.storage().persistent().set(&key, &nonce);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
@@ -5,8 +5,14 @@ use soroban_sdk::{symbol_short, Address, Env, Symbol};
 pub enum DataKey {
     Balance(Address),
     State(Address), // true = frozen
+    GlobalFrozen,   // true = every account frozen
+    MinBalance,     // smallest non-zero balance a transfer may leave behind
 }
 
+pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
+pub(crate) const BALANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const BALANCE_LIFETIME_THRESHOLD: u32 = BALANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
 fn total_supply_key() -> Symbol {
     symbol_short!("TSUPPLY")
 }
@@ -27,6 +33,21 @@ pub fn read_balance(e: &Env, addr: Address) -> i128 {
 pub fn write_balance(e: &Env, addr: Address, amount: i128) {
     let key = DataKey::Balance(addr);
     e.storage().persistent().set(&key, &amount);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+/// Extend the TTL of `addr`'s balance and freeze state entries so an idle
+/// balance isn't archived and a freeze doesn't lapse.
+pub fn bump_balance(e: &Env, addr: Address) {
+    let key = DataKey::Balance(addr.clone());
+    if e.storage().persistent().has(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+    bump_state(e, addr);
 }
 
 pub fn read_state(e: &Env, addr: Address) -> bool {
@@ -37,10 +58,38 @@ pub fn read_state(e: &Env, addr: Address) -> bool {
 pub fn write_state(e: &Env, addr: Address, is_frozen: bool) {
     let key = DataKey::State(addr);
     e.storage().persistent().set(&key, &is_frozen);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+/// Extend the TTL of `addr`'s freeze state, if it has one, so a freeze can't
+/// silently expire and unfreeze the account.
+fn bump_state(e: &Env, addr: Address) {
+    let key = DataKey::State(addr);
+    if e.storage().persistent().has(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+}
+
+pub fn read_global_frozen(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get(&DataKey::GlobalFrozen)
+        .unwrap_or(false)
+}
+
+pub fn write_global_frozen(e: &Env, is_frozen: bool) {
+    e.storage()
+        .instance()
+        .set(&DataKey::GlobalFrozen, &is_frozen);
 }
 
 pub fn check_not_frozen(e: &Env, addr: &Address) {
-    if read_state(e, addr.clone()) {
+    bump_state(e, addr.clone());
+    if read_global_frozen(e) || read_state(e, addr.clone()) {
         panic!("account is frozen");
     }
 }
@@ -49,8 +98,6 @@ pub fn receive_balance(e: &Env, addr: Address, amount: i128) {
     check_not_frozen(e, &addr);
     let balance = read_balance(e, addr.clone());
     write_balance(e, addr, balance + amount);
-    write_total_supply(e, read_total_supply(e) + amount);
-    write_total_supply(e, read_total_supply(e) - amount);
 }
 
 pub fn spend_balance(e: &Env, addr: Address, amount: i128) {
@@ -61,3 +108,51 @@ pub fn spend_balance(e: &Env, addr: Address, amount: i128) {
     }
     write_balance(e, addr, balance - amount);
 }
+
+pub fn read_min_balance(e: &Env) -> i128 {
+    e.storage()
+        .instance()
+        .get(&DataKey::MinBalance)
+        .unwrap_or(0)
+}
+
+pub fn write_min_balance(e: &Env, amount: i128) {
+    e.storage().instance().set(&DataKey::MinBalance, &amount);
+}
+
+/// Move `amount` from `from` to `to`.
+///
+/// Both accounts are checked for a freeze before any balance changes. A
+/// self-transfer is validated like any other transfer but skips the
+/// storage writes, since the balance would end up unchanged. A transfer must
+/// either empty the sender or leave at least the configured minimum balance.
+pub fn transfer_balance(e: &Env, from: Address, to: Address, amount: i128) {
+    check_not_frozen(e, &from);
+    check_not_frozen(e, &to);
+    if from == to {
+        if read_balance(e, from) < amount {
+            panic!("insufficient balance");
+        }
+        return;
+    }
+    spend_balance(e, from.clone(), amount);
+    let remaining = read_balance(e, from);
+    if remaining > 0 && remaining < read_min_balance(e) {
+        panic!("transfer would leave dust");
+    }
+    receive_balance(e, to, amount);
+}
+
+/// Credit newly minted tokens to `addr` and grow the total supply.
+pub fn mint_balance(e: &Env, addr: Address, amount: i128) {
+    receive_balance(e, addr, amount);
+    write_total_supply(e, read_total_supply(e) + amount);
+}
+
+/// Destroy tokens held by `addr` and shrink the total supply.
+///
+/// Every burn path must go through here so supply stays consistent.
+pub fn burn_balance(e: &Env, addr: Address, amount: i128) {
+    spend_balance(e, addr, amount);
+    write_total_supply(e, read_total_supply(e) - amount);
+}
@@ -22,3 +22,45 @@ pub struct BurnEvent {
     pub from: Address,
     pub amount: i128,
 }
+
+/// Emitted when the admin freezes an account.
+#[contractevent]
+pub struct FrozenEvent {
+    #[topic]
+    pub id: Address,
+}
+
+/// Emitted when the admin unfreezes an account.
+#[contractevent]
+pub struct UnfrozenEvent {
+    #[topic]
+    pub id: Address,
+}
+
+/// Emitted when the admin freezes every account.
+#[contractevent]
+pub struct GlobalFrozenEvent {
+    #[topic]
+    pub admin: Address,
+}
+
+/// Emitted when the admin lifts the global freeze.
+#[contractevent]
+pub struct GlobalUnfrozenEvent {
+    #[topic]
+    pub admin: Address,
+}
+
+/// Emitted when the admin grants the minter role.
+#[contractevent]
+pub struct MinterAddedEvent {
+    #[topic]
+    pub minter: Address,
+}
+
+/// Emitted when the admin revokes the minter role.
+#[contractevent]
+pub struct MinterRemovedEvent {
+    #[topic]
+    pub minter: Address,
+}
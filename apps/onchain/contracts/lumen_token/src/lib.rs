@@ -5,10 +5,17 @@ mod allowance;
 mod balance;
 mod events;
 mod metadata;
+mod receiver;
 mod test;
 
-use events::{AdminChangedEvent, BurnEvent, UpgradedEvent};
-use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, String};
+use allowance::AllowanceDataKey;
+use events::{
+    AdminChangedEvent, BurnEvent, FrozenEvent, GlobalFrozenEvent, GlobalUnfrozenEvent,
+    MinterAddedEvent, MinterRemovedEvent, UnfrozenEvent, UpgradedEvent,
+};
+pub use metadata::TokenMetadata;
+pub use receiver::{TokenReceiver, TokenReceiverClient};
+use soroban_sdk::{contract, contractimpl, xdr::ToXdr, Address, Bytes, BytesN, Env, String, Vec};
 
 #[contract]
 pub struct LumenToken;
@@ -26,7 +33,54 @@ impl LumenToken {
     pub fn mint(e: Env, to: Address, amount: i128) {
         let admin = admin::read_administrator(&e);
         admin.require_auth();
-        balance::receive_balance(&e, to, amount);
+        balance::mint_balance(&e, to, amount);
+    }
+
+    /// Mint as a holder of the minter role rather than the admin.
+    pub fn minter_mint(e: Env, minter: Address, to: Address, amount: i128) {
+        if !admin::is_minter(&e, &minter) {
+            panic!("not a minter");
+        }
+        minter.require_auth();
+        balance::mint_balance(&e, to, amount);
+    }
+
+    /// Grant the minter role to `minter`. Emits [`MinterAddedEvent`].
+    pub fn add_minter(e: Env, minter: Address) {
+        let admin = admin::read_administrator(&e);
+        admin.require_auth();
+        let mut minters = admin::read_minters(&e);
+        if minters.contains(&minter) {
+            return;
+        }
+        minters.push_back(minter.clone());
+        admin::write_minters(&e, &minters);
+        MinterAddedEvent { minter }.publish(&e);
+    }
+
+    /// Revoke the minter role from `minter`. Emits [`MinterRemovedEvent`].
+    pub fn remove_minter(e: Env, minter: Address) {
+        let admin = admin::read_administrator(&e);
+        admin.require_auth();
+        let mut minters = admin::read_minters(&e);
+        if let Some(index) = minters.first_index_of(&minter) {
+            minters.remove(index);
+            admin::write_minters(&e, &minters);
+            MinterRemovedEvent { minter }.publish(&e);
+        }
+    }
+
+    /// List every address that can mint: the admin first, then granted minters.
+    pub fn get_minters(e: Env) -> Vec<Address> {
+        let mut minters = Vec::new(&e);
+        minters.push_back(admin::read_administrator(&e));
+        minters.append(&admin::read_minters(&e));
+        minters
+    }
+
+    /// Get the current token administrator.
+    pub fn admin(e: Env) -> Address {
+        admin::read_administrator(&e)
     }
 
     /// Transfer the admin role to `new_admin`. Emits [`AdminChangedEvent`].
@@ -44,22 +98,132 @@ impl LumenToken {
     pub fn freeze(e: Env, id: Address) {
         let admin = admin::read_administrator(&e);
         admin.require_auth();
-        balance::write_state(&e, id, true);
+        balance::write_state(&e, id.clone(), true);
+        FrozenEvent { id }.publish(&e);
     }
 
     pub fn unfreeze(e: Env, id: Address) {
         let admin = admin::read_administrator(&e);
         admin.require_auth();
-        balance::write_state(&e, id, false);
+        balance::write_state(&e, id.clone(), false);
+        UnfrozenEvent { id }.publish(&e);
+    }
+
+    /// Freeze every account at once. Per-account freezes are kept and still
+    /// apply after [`unfreeze_all`](Self::unfreeze_all).
+    pub fn freeze_all(e: Env) {
+        let admin = admin::read_administrator(&e);
+        admin.require_auth();
+        balance::write_global_frozen(&e, true);
+        GlobalFrozenEvent { admin }.publish(&e);
+    }
+
+    /// Lift a [`freeze_all`](Self::freeze_all).
+    pub fn unfreeze_all(e: Env) {
+        let admin = admin::read_administrator(&e);
+        admin.require_auth();
+        balance::write_global_frozen(&e, false);
+        GlobalUnfrozenEvent { admin }.publish(&e);
+    }
+
+    /// Set the smallest non-zero balance a transfer may leave the sender with.
+    /// Zero (the default) disables the check.
+    pub fn set_min_balance(e: Env, amount: i128) {
+        let admin = admin::read_administrator(&e);
+        admin.require_auth();
+        if amount < 0 {
+            panic!("negative minimum balance");
+        }
+        balance::write_min_balance(&e, amount);
+    }
+
+    /// Get the minimum balance enforced by transfers.
+    pub fn min_balance(e: Env) -> i128 {
+        balance::read_min_balance(&e)
+    }
+
+    /// Whether a [`freeze_all`](Self::freeze_all) is in effect.
+    pub fn is_frozen_all(e: Env) -> bool {
+        balance::read_global_frozen(&e)
     }
 
     pub fn allowance(e: Env, from: Address, spender: Address) -> i128 {
         allowance::read_allowance(&e, from, spender).amount
     }
 
+    /// List the active allowances granted by `from`.
+    pub fn allowances_of(e: Env, from: Address) -> Vec<AllowanceDataKey> {
+        allowance::read_allowances_of(&e, from)
+    }
+
     pub fn approve(e: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
         from.require_auth();
         balance::check_not_frozen(&e, &from);
+        if expiration_ledger != 0 && expiration_ledger < e.ledger().sequence() {
+            panic!("expiration_ledger is in the past");
+        }
+        allowance::write_allowance(&e, from, spender, amount, expiration_ledger);
+    }
+
+    /// Approve `spender` for `ttl_ledgers` ledgers from the current sequence.
+    pub fn approve_for(e: Env, from: Address, spender: Address, amount: i128, ttl_ledgers: u32) {
+        let expiration_ledger = e
+            .ledger()
+            .sequence()
+            .checked_add(ttl_ledgers)
+            .expect("expiration_ledger overflow");
+        Self::approve(e, from, spender, amount, expiration_ledger);
+    }
+
+    /// Register the ed25519 key whose signatures [`permit`](Self::permit) accepts for `from`.
+    ///
+    /// This is the one step that needs `from`'s own auth: permits can only be
+    /// relayed for an owner who has registered a key.
+    pub fn set_permit_key(e: Env, from: Address, public_key: BytesN<32>) {
+        from.require_auth();
+        allowance::write_permit_key(&e, from, &public_key);
+    }
+
+    /// Get the nonce the next permit signed for `from` must use.
+    pub fn permit_nonce(e: Env, from: Address) -> u64 {
+        allowance::read_permit_nonce(&e, from)
+    }
+
+    /// Approve on behalf of `from` using a signature instead of `from`'s auth, so a
+    /// relayer can submit it.
+    ///
+    /// `signature` is made with `from`'s permit key over the XDR encoding of
+    /// `(token, from, spender, amount, expiration_ledger, nonce)`. Each nonce is
+    /// accepted once. `from` must first register the key with
+    /// [`set_permit_key`](Self::set_permit_key); the account's own key isn't
+    /// used, since it may not be one of its signers.
+    pub fn permit(
+        e: Env,
+        from: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+        signature: BytesN<64>,
+    ) {
+        let public_key = allowance::read_permit_key(&e, from.clone()).expect("no permit key");
+        let nonce = allowance::read_permit_nonce(&e, from.clone());
+        let message = (
+            e.current_contract_address(),
+            from.clone(),
+            spender.clone(),
+            amount,
+            expiration_ledger,
+            nonce,
+        )
+            .to_xdr(&e);
+        e.crypto().ed25519_verify(&public_key, &message, &signature);
+        allowance::write_permit_nonce(&e, from.clone(), nonce + 1);
+        allowance::bump_permit_key(&e, from.clone());
+
+        balance::check_not_frozen(&e, &from);
+        if expiration_ledger != 0 && expiration_ledger < e.ledger().sequence() {
+            panic!("expiration_ledger is in the past");
+        }
         allowance::write_allowance(&e, from, spender, amount, expiration_ledger);
     }
 
@@ -67,10 +231,46 @@ impl LumenToken {
         balance::read_balance(&e, id)
     }
 
+    /// Extend the TTL of `id`'s balance and freeze state entries. Anyone may pay to
+    /// keep a balance alive, and the admin can keep a freeze from lapsing.
+    pub fn bump_balance_ttl(e: Env, id: Address) {
+        balance::bump_balance(&e, id);
+    }
+
+    pub fn total_supply(e: Env) -> i128 {
+        balance::read_total_supply(&e)
+    }
+
     pub fn transfer(e: Env, from: Address, to: Address, amount: i128) {
         from.require_auth();
-        balance::spend_balance(&e, from.clone(), amount);
-        balance::receive_balance(&e, to, amount);
+        balance::transfer_balance(&e, from, to, amount);
+    }
+
+    /// Transfer to a contract and notify it through
+    /// [`TokenReceiver::on_token_received`] in the same call.
+    pub fn transfer_and_call(
+        e: Env,
+        from: Address,
+        to_contract: Address,
+        amount: i128,
+        data: Bytes,
+    ) {
+        from.require_auth();
+        balance::transfer_balance(&e, from.clone(), to_contract.clone(), amount);
+        TokenReceiverClient::new(&e, &to_contract).on_token_received(
+            &e.current_contract_address(),
+            &from,
+            &amount,
+            &data,
+        );
+    }
+
+    /// Transfer like [`transfer`](Self::transfer), but only up to `deadline_ledger`.
+    pub fn transfer_before(e: Env, from: Address, to: Address, amount: i128, deadline_ledger: u32) {
+        if e.ledger().sequence() > deadline_ledger {
+            panic!("transfer deadline passed");
+        }
+        Self::transfer(e, from, to, amount);
     }
 
     pub fn transfer_from(e: Env, spender: Address, from: Address, to: Address, amount: i128) {
@@ -78,22 +278,22 @@ impl LumenToken {
         balance::check_not_frozen(&e, &spender);
 
         allowance::spend_allowance(&e, from.clone(), spender, amount);
-        balance::spend_balance(&e, from.clone(), amount);
-        balance::receive_balance(&e, to, amount);
+        balance::transfer_balance(&e, from, to, amount);
     }
 
     pub fn burn(e: Env, from: Address, amount: i128) {
         from.require_auth();
         balance::check_not_frozen(&e, &from);
-        balance::spend_balance(&e, from.clone(), amount);
+        balance::burn_balance(&e, from.clone(), amount);
         BurnEvent { from, amount }.publish(&e);
     }
 
     pub fn burn_from(e: Env, spender: Address, from: Address, amount: i128) {
         spender.require_auth();
         balance::check_not_frozen(&e, &spender);
+        balance::check_not_frozen(&e, &from);
         allowance::spend_allowance(&e, from.clone(), spender, amount);
-        balance::spend_balance(&e, from.clone(), amount);
+        balance::burn_balance(&e, from.clone(), amount);
         BurnEvent { from, amount }.publish(&e);
     }
 
@@ -109,6 +309,29 @@ impl LumenToken {
         metadata::read_symbol(&e)
     }
 
+    /// Get decimals, name, symbol and total supply in one call.
+    pub fn metadata(e: Env) -> TokenMetadata {
+        TokenMetadata {
+            decimal: metadata::read_decimal(&e),
+            name: metadata::read_name(&e),
+            symbol: metadata::read_symbol(&e),
+            total_supply: balance::read_total_supply(&e),
+        }
+    }
+
+    /// Split `amount` into (whole, fractional) units using `decimals` places.
+    ///
+    /// Pure helper for display; pass this token's [`decimals`](Self::decimals) to
+    /// format its own amounts.
+    pub fn to_display_units(amount: i128, decimals: u32) -> (i128, i128) {
+        metadata::to_display_units(amount, decimals)
+    }
+
+    /// Return this contract's version identifier.
+    pub fn version() -> u32 {
+        1
+    }
+
     /// Upgrade the contract WASM to a new hash.
     ///
     /// Only the stored admin may call this. Emits [`UpgradedEvent`] on success.
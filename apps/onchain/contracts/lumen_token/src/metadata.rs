@@ -23,8 +23,28 @@ pub fn read_symbol(e: &Env) -> String {
     e.storage().instance().get(&key).unwrap()
 }
 
+/// Display metadata bundled for a single read.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[soroban_sdk::contracttype]
+pub struct TokenMetadata {
+    pub decimal: u32,
+    pub name: String,
+    pub symbol: String,
+    pub total_supply: i128,
+}
+
 pub fn write_metadata(e: &Env, decimal: u32, name: String, symbol: String) {
     e.storage().instance().set(&DataKey::Decimals, &decimal);
     e.storage().instance().set(&DataKey::Name, &name);
     e.storage().instance().set(&DataKey::Symbol, &symbol);
 }
+
+/// Split `amount` into whole and fractional units for a token with `decimals` places.
+///
+/// Both parts carry the sign of `amount`, e.g. -1.5 with 1 decimal is (-1, -5).
+pub fn to_display_units(amount: i128, decimals: u32) -> (i128, i128) {
+    let unit = 10i128
+        .checked_pow(decimals)
+        .unwrap_or_else(|| panic!("decimals too large"));
+    (amount / unit, amount % unit)
+}
@@ -0,0 +1,10 @@
+use soroban_sdk::{contractclient, Address, Bytes, Env};
+
+/// Interface a contract implements to be notified by `transfer_and_call`.
+///
+/// The callback runs after the tokens have been credited to the receiver; if it
+/// panics the whole transfer is rolled back.
+#[contractclient(name = "TokenReceiverClient")]
+pub trait TokenReceiver {
+    fn on_token_received(env: Env, token: Address, from: Address, amount: i128, data: Bytes);
+}
@@ -1,8 +1,15 @@
 #![cfg(test)]
 extern crate std;
 
-use crate::{LumenToken, LumenTokenClient};
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String};
+use crate::{allowance, balance, LumenToken, LumenTokenClient};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{storage::Persistent as _, Address as _, Events, Ledger},
+    vec,
+    xdr::ToXdr,
+    Address, Bytes, BytesN, Env, String, Symbol, TryFromVal,
+};
 
 #[test]
 fn test_token() {
@@ -38,6 +45,41 @@ fn test_token() {
     assert_eq!(client.balance(&user2), 300);
 }
 
+#[test]
+fn test_burn_paths_reduce_total_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    assert_eq!(client.total_supply(), 1000);
+
+    // Transfers do not change supply
+    client.transfer(&user1, &user2, &300);
+    assert_eq!(client.total_supply(), 1000);
+
+    client.burn(&user1, &200);
+    assert_eq!(client.total_supply(), 800);
+
+    client.approve(&user2, &spender, &100, &1000);
+    client.burn_from(&spender, &user2, &100);
+    assert_eq!(client.total_supply(), 700);
+}
+
 #[test]
 #[should_panic(expected = "account is frozen")]
 fn test_freeze() {
@@ -59,9 +101,812 @@ fn test_freeze() {
     );
 
     client.mint(&user1, &1000);
-    client.freeze(&user1);
+    client.freeze(&user1);
+
+    client.transfer(&user1, &user2, &100);
+}
+
+#[test]
+fn test_transfer_to_frozen_recipient_fails_cleanly() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.freeze(&user2);
+
+    assert!(client.try_transfer(&user1, &user2, &100).is_err());
+    assert_eq!(client.balance(&user1), 1000);
+    assert_eq!(client.balance(&user2), 0);
+}
+
+#[test]
+#[should_panic(expected = "account is frozen")]
+fn test_frozen_recipient_checked_before_sender_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.freeze(&user2);
+
+    // Would otherwise fail with "insufficient balance"
+    client.transfer(&user1, &user2, &5000);
+}
+
+fn last_event_name(env: &Env) -> Symbol {
+    let (_, topics, _) = env.events().all().last().unwrap();
+    Symbol::try_from_val(env, &topics.get(0).unwrap()).unwrap()
+}
+
+#[test]
+fn test_freeze_unfreeze_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.freeze(&user1);
+    assert_eq!(last_event_name(&env), Symbol::new(&env, "frozen_event"));
+
+    client.unfreeze(&user1);
+    assert_eq!(last_event_name(&env), Symbol::new(&env, "unfrozen_event"));
+}
+
+#[test]
+#[should_panic(expected = "account is frozen")]
+fn test_freeze_all_blocks_transfers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.freeze_all();
+    assert!(client.is_frozen_all());
+
+    client.transfer(&user1, &user2, &100);
+}
+
+#[test]
+fn test_unfreeze_all_resumes_transfers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let user3 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.mint(&user3, &1000);
+    client.freeze(&user3);
+
+    client.freeze_all();
+    assert_eq!(
+        last_event_name(&env),
+        Symbol::new(&env, "global_frozen_event")
+    );
+    assert!(client.try_transfer(&user1, &user2, &100).is_err());
+
+    client.unfreeze_all();
+    assert_eq!(
+        last_event_name(&env),
+        Symbol::new(&env, "global_unfrozen_event")
+    );
+    assert!(!client.is_frozen_all());
+
+    client.transfer(&user1, &user2, &100);
+    assert_eq!(client.balance(&user2), 100);
+
+    // Per-account freezes are independent of the global flag
+    assert!(client.try_transfer(&user3, &user2, &100).is_err());
+}
+
+#[test]
+#[should_panic(expected = "account is frozen")]
+fn test_burn_from_frozen_account() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.approve(&user1, &spender, &500, &1000);
+    client.freeze(&user1);
+
+    client.burn_from(&spender, &user1, &100);
+}
+
+#[test]
+#[should_panic(expected = "expiration_ledger is in the past")]
+fn test_approve_past_expiration_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.approve(&user1, &spender, &500, &99);
+}
+
+#[test]
+fn test_approve_for_sets_relative_expiration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.approve_for(&user1, &spender, &500, &50);
+
+    let stored = env.as_contract(&contract_id, || {
+        allowance::read_allowance(&env, user1.clone(), spender.clone())
+    });
+    assert_eq!(stored.amount, 500);
+    assert_eq!(stored.expiration_ledger, 150);
+    assert_eq!(client.allowance(&user1, &spender), 500);
+}
+
+#[test]
+fn test_transfer_from_exact_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.approve(&user1, &spender, &500, &200);
+
+    client.transfer_from(&spender, &user1, &user2, &500);
+    assert_eq!(client.allowance(&user1, &spender), 0);
+    assert_eq!(client.balance(&user2), 500);
+}
+
+#[test]
+#[should_panic(expected = "insufficient allowance")]
+fn test_transfer_from_over_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.approve(&user1, &spender, &500, &200);
+
+    client.transfer_from(&spender, &user1, &user2, &501);
+}
+
+/// Receiver that records the last `on_token_received` callback
+#[contract]
+pub struct MockReceiver;
+
+#[contractimpl]
+impl MockReceiver {
+    pub fn on_token_received(env: Env, token: Address, from: Address, amount: i128, data: Bytes) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("last"), &(token, from, amount, data));
+    }
+
+    pub fn last(env: Env) -> Option<(Address, Address, i128, Bytes)> {
+        env.storage().instance().get(&symbol_short!("last"))
+    }
+}
+
+#[test]
+fn test_transfer_and_call() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+    client.mint(&user, &1000);
+
+    let receiver_id = env.register(MockReceiver, ());
+    let receiver = MockReceiverClient::new(&env, &receiver_id);
+    let data = Bytes::from_array(&env, &[1, 2, 3]);
+
+    client.transfer_and_call(&user, &receiver_id, &400, &data);
+
+    assert_eq!(client.balance(&user), 600);
+    assert_eq!(client.balance(&receiver_id), 400);
+    assert_eq!(receiver.last(), Some((contract_id, user, 400, data)));
+}
+
+#[test]
+fn test_transfer_and_call_to_non_receiver_reverts() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+    client.mint(&user, &1000);
+
+    // A contract without the callback rejects the transfer entirely
+    let result = client.try_transfer_and_call(&user, &contract_id, &400, &Bytes::new(&env));
+    assert!(result.is_err());
+    assert_eq!(client.balance(&user), 1000);
+    assert_eq!(client.balance(&contract_id), 0);
+}
+
+/// Sign a permit the way `permit` expects
+fn sign_permit(
+    client: &LumenTokenClient,
+    key: &SigningKey,
+    from: &Address,
+    spender: &Address,
+    amount: i128,
+    expiration_ledger: u32,
+    nonce: u64,
+) -> BytesN<64> {
+    let env = &client.env;
+    let message = (
+        client.address.clone(),
+        from.clone(),
+        spender.clone(),
+        amount,
+        expiration_ledger,
+        nonce,
+    )
+        .to_xdr(env);
+    let message: std::vec::Vec<u8> = message.iter().collect();
+    BytesN::from_array(env, &key.sign(&message).to_bytes())
+}
+
+fn setup_permit(env: &Env) -> (LumenTokenClient<'_>, Address, SigningKey) {
+    let admin = Address::generate(env);
+    let owner = Address::generate(env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(env, &contract_id);
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(env, "LumenPulse"),
+        &String::from_str(env, "LMN"),
+    );
+
+    let key = SigningKey::from_bytes(&[7; 32]);
+    client.mock_all_auths().set_permit_key(
+        &owner,
+        &BytesN::from_array(env, key.verifying_key().as_bytes()),
+    );
+    (client, owner, key)
+}
+
+#[test]
+fn test_permit_sets_allowance() {
+    let env = Env::default();
+    let (client, owner, key) = setup_permit(&env);
+    let spender = Address::generate(&env);
+
+    assert_eq!(client.permit_nonce(&owner), 0);
+    let signature = sign_permit(&client, &key, &owner, &spender, 500, 1_000, 0);
+
+    // No auth is mocked: the signature alone authorizes the approval
+    client.permit(&owner, &spender, &500, &1_000, &signature);
+    assert_eq!(client.allowance(&owner, &spender), 500);
+    assert_eq!(client.permit_nonce(&owner), 1);
+    assert!(env.auths().is_empty());
+
+    // The same signature cannot be replayed once the nonce has moved on
+    let replay = client.try_permit(&owner, &spender, &500, &1_000, &signature);
+    assert!(replay.is_err());
+}
+
+#[test]
+fn test_permit_rejects_invalid_signature() {
+    let env = Env::default();
+    let (client, owner, key) = setup_permit(&env);
+    let spender = Address::generate(&env);
+
+    // Signed by a key other than the registered one
+    let other_key = SigningKey::from_bytes(&[9; 32]);
+    let signature = sign_permit(&client, &other_key, &owner, &spender, 500, 1_000, 0);
+    assert!(client
+        .try_permit(&owner, &spender, &500, &1_000, &signature)
+        .is_err());
+
+    // Signed by the right key but for a different amount
+    let signature = sign_permit(&client, &key, &owner, &spender, 100, 1_000, 0);
+    assert!(client
+        .try_permit(&owner, &spender, &500, &1_000, &signature)
+        .is_err());
+
+    assert_eq!(client.allowance(&owner, &spender), 0);
+    assert_eq!(client.permit_nonce(&owner), 0);
+}
+
+#[test]
+fn test_permit_requires_registered_key_and_keeps_it_alive() {
+    let env = Env::default();
+    let (client, owner, key) = setup_permit(&env);
+    let spender = Address::generate(&env);
+
+    // An owner who never registered a key can't have permits relayed
+    let newcomer = Address::generate(&env);
+    let signature = sign_permit(&client, &key, &newcomer, &spender, 500, 1_000, 0);
+    assert!(client
+        .try_permit(&newcomer, &spender, &500, &1_000, &signature)
+        .is_err());
+
+    // Registering the key is the one call that needs the owner's auth
+    client.mock_all_auths().set_permit_key(
+        &newcomer,
+        &BytesN::from_array(&env, key.verifying_key().as_bytes()),
+    );
+    assert_eq!(env.auths()[0].0, newcomer);
+
+    // Using a permit keeps both the key and the nonce from expiring
+    let ttl = |entry: allowance::DataKey| {
+        env.as_contract(&client.address, || {
+            env.storage().persistent().get_ttl(&entry)
+        })
+    };
+    let sequence = balance::BALANCE_LIFETIME_THRESHOLD + 1;
+    env.ledger().set_sequence_number(sequence);
+    assert!(
+        ttl(allowance::DataKey::PermitKey(owner.clone())) < balance::BALANCE_LIFETIME_THRESHOLD
+    );
+    let expiration = sequence + 1_000;
+    let signature = sign_permit(&client, &key, &owner, &spender, 500, expiration, 0);
+    client.permit(&owner, &spender, &500, &expiration, &signature);
+    assert_eq!(
+        ttl(allowance::DataKey::PermitKey(owner.clone())),
+        balance::BALANCE_BUMP_AMOUNT
+    );
+    assert_eq!(
+        ttl(allowance::DataKey::PermitNonce(owner)),
+        balance::BALANCE_BUMP_AMOUNT
+    );
+}
+
+#[test]
+fn test_metadata_bundle() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+    client.mint(&user, &1234);
+
+    let metadata = client.metadata();
+    assert_eq!(metadata.decimal, client.decimals());
+    assert_eq!(metadata.name, client.name());
+    assert_eq!(metadata.symbol, client.symbol());
+    assert_eq!(metadata.total_supply, client.total_supply());
+    assert_eq!(metadata.total_supply, 1234);
+}
+
+#[test]
+fn test_version() {
+    let env = Env::default();
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    assert_eq!(client.version(), 1);
+}
+
+#[test]
+fn test_transfer_before_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+
+    // The deadline ledger itself is still accepted
+    client.transfer_before(&user1, &user2, &300, &100);
+    assert_eq!(client.balance(&user1), 700);
+    assert_eq!(client.balance(&user2), 300);
+}
+
+#[test]
+#[should_panic(expected = "transfer deadline passed")]
+fn test_transfer_after_deadline_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(101);
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.transfer_before(&user1, &user2, &300, &100);
+}
 
-    client.transfer(&user1, &user2, &100);
+#[test]
+fn test_min_balance_guard() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    assert_eq!(client.min_balance(), 0);
+    client.set_min_balance(&100);
+
+    // Leaving exactly the minimum is allowed
+    client.transfer(&user1, &user2, &900);
+    assert_eq!(client.balance(&user1), 100);
+
+    // Moving the full balance is allowed
+    client.transfer(&user2, &user1, &900);
+    assert_eq!(client.balance(&user2), 0);
+
+    // Leaving less than the minimum is rejected
+    assert!(client.try_transfer(&user1, &user2, &901).is_err());
+    assert_eq!(client.balance(&user1), 1000);
+}
+
+#[test]
+#[should_panic(expected = "transfer would leave dust")]
+fn test_min_balance_rejects_dust() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.set_min_balance(&100);
+    client.transfer(&user1, &user2, &950);
+}
+
+#[test]
+fn test_self_transfer_is_noop() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+
+    client.transfer(&user1, &user1, &400);
+    assert_eq!(client.balance(&user1), 1000);
+    assert_eq!(client.total_supply(), 1000);
+}
+
+#[test]
+#[should_panic(expected = "insufficient balance")]
+fn test_self_transfer_exceeding_balance_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.transfer(&user1, &user1, &1001);
+}
+
+#[test]
+fn test_allowances_of() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let spender1 = Address::generate(&env);
+    let spender2 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.approve(&user1, &spender1, &100, &1000);
+    client.approve(&user1, &spender2, &200, &1000);
+
+    let allowances = client.allowances_of(&user1);
+    assert_eq!(allowances.len(), 2);
+    assert_eq!(allowances.get(0).unwrap().spender, spender1);
+    assert_eq!(allowances.get(1).unwrap().spender, spender2);
+
+    // Revoking removes the spender
+    client.approve(&user1, &spender1, &0, &1000);
+    let allowances = client.allowances_of(&user1);
+    assert_eq!(allowances.len(), 1);
+    assert_eq!(allowances.get(0).unwrap().spender, spender2);
+    assert_eq!(spender_list(&env, &contract_id, &user1).len(), 1);
+}
+
+/// The owner's stored spender list, including any not yet pruned
+fn spender_list(env: &Env, contract_id: &Address, from: &Address) -> soroban_sdk::Vec<Address> {
+    env.as_contract(contract_id, || {
+        env.storage()
+            .persistent()
+            .get(&allowance::DataKey::Spenders(from.clone()))
+            .unwrap_or(soroban_sdk::Vec::new(env))
+    })
+}
+
+fn spender_ttl(env: &Env, contract_id: &Address, from: &Address) -> u32 {
+    env.as_contract(contract_id, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&allowance::DataKey::Spenders(from.clone()))
+    })
+}
+
+#[test]
+fn test_spender_list_prunes_expired_and_extends_ttl() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let spender1 = Address::generate(&env);
+    let spender2 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.approve(&user1, &spender1, &100, &100);
+    assert_eq!(
+        spender_ttl(&env, &contract_id, &user1),
+        balance::BALANCE_BUMP_AMOUNT
+    );
+
+    // The expired allowance is dropped the next time the list is written
+    env.ledger().set_sequence_number(101);
+    client.approve(&user1, &spender2, &200, &1000);
+    assert_eq!(
+        spender_list(&env, &contract_id, &user1),
+        vec![&env, spender2.clone()]
+    );
+
+    // Spending part of an allowance leaves the list untouched, so a stale
+    // entry planted in it survives
+    let stale = Address::generate(&env);
+    let planted = vec![&env, stale.clone(), spender2.clone()];
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&allowance::DataKey::Spenders(user1.clone()), &planted);
+    });
+    client.transfer_from(&spender2, &user1, &spender2, &50);
+    assert_eq!(spender_list(&env, &contract_id, &user1), planted);
+
+    // Spending an allowance down to zero removes its spender
+    client.transfer_from(&spender2, &user1, &spender2, &150);
+    assert_eq!(spender_list(&env, &contract_id, &user1), vec![&env, stale]);
+    assert_eq!(client.allowances_of(&user1).len(), 0);
 }
 
 // ---------------------------------------------------------------------------
@@ -86,8 +931,11 @@ fn test_set_admin_transfers_role() {
         &String::from_str(&env, "LMN"),
     );
 
+    assert_eq!(client.admin(), admin);
+
     // Rotate admin
     client.set_admin(&new_admin);
+    assert_eq!(client.admin(), new_admin);
 
     // Verify the new admin can mint (only admin can mint)
     client.mint(&new_admin, &1000);
@@ -116,3 +964,198 @@ fn test_only_admin_can_upgrade() {
     let dummy: BytesN<32> = BytesN::from_array(&env, &[0u8; 32]);
     client.upgrade(&non_admin, &dummy); // must panic
 }
+
+#[test]
+fn test_get_minters() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let minter1 = Address::generate(&env);
+    let minter2 = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    assert_eq!(client.get_minters(), vec![&env, admin.clone()]);
+
+    client.add_minter(&minter1);
+    client.add_minter(&minter2);
+    client.add_minter(&minter1);
+    assert_eq!(
+        client.get_minters(),
+        vec![&env, admin.clone(), minter1.clone(), minter2.clone()]
+    );
+
+    client.minter_mint(&minter2, &user1, &500);
+    assert_eq!(client.balance(&user1), 500);
+    assert_eq!(client.total_supply(), 500);
+
+    client.remove_minter(&minter1);
+    assert_eq!(client.get_minters(), vec![&env, admin, minter2]);
+}
+
+#[test]
+#[should_panic(expected = "not a minter")]
+fn test_minter_mint_requires_role() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.minter_mint(&user1, &user1, &500);
+}
+
+#[test]
+fn test_to_display_units() {
+    use crate::metadata::to_display_units;
+
+    assert_eq!(to_display_units(12_345_678, 7), (1, 2_345_678));
+    assert_eq!(to_display_units(10_000_000, 7), (1, 0));
+    assert_eq!(to_display_units(999, 7), (0, 999));
+    assert_eq!(
+        to_display_units(1_500_000_000_000_000_000, 18),
+        (1, 500_000_000_000_000_000)
+    );
+    assert_eq!(to_display_units(42, 0), (42, 0));
+    assert_eq!(to_display_units(-15, 1), (-1, -5));
+    assert_eq!(to_display_units(0, 7), (0, 0));
+}
+
+#[test]
+fn test_to_display_units_contract_fn() {
+    let env = Env::default();
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    assert_eq!(client.to_display_units(&25_000_001, &7), (2, 5_000_001));
+}
+
+#[test]
+#[should_panic(expected = "decimals too large")]
+fn test_to_display_units_decimals_too_large() {
+    crate::metadata::to_display_units(1, 39);
+}
+
+/// Remaining TTL of `id`'s balance entry
+fn balance_ttl(env: &Env, contract_id: &Address, id: &Address) -> u32 {
+    env.as_contract(contract_id, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&balance::DataKey::Balance(id.clone()))
+    })
+}
+
+#[test]
+fn test_balance_survives_ledger_advance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+
+    client.mint(&user1, &1000);
+    client.transfer(&user1, &user2, &400);
+
+    // Balance writes extend the entry well past the default minimum TTL
+    assert!(balance_ttl(&env, &contract_id, &user1) >= balance::BALANCE_LIFETIME_THRESHOLD);
+    assert!(balance_ttl(&env, &contract_id, &user2) >= balance::BALANCE_LIFETIME_THRESHOLD);
+
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + balance::BALANCE_LIFETIME_THRESHOLD);
+    assert_eq!(client.balance(&user1), 600);
+
+    // An explicit bump keeps an idle balance alive for another period
+    assert!(balance_ttl(&env, &contract_id, &user2) < balance::BALANCE_LIFETIME_THRESHOLD);
+    client.bump_balance_ttl(&user2);
+    assert!(balance_ttl(&env, &contract_id, &user2) >= balance::BALANCE_LIFETIME_THRESHOLD);
+    assert_eq!(client.balance(&user2), 400);
+}
+
+/// Remaining TTL of `id`'s freeze state entry
+fn state_ttl(env: &Env, contract_id: &Address, id: &Address) -> u32 {
+    env.as_contract(contract_id, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&balance::DataKey::State(id.clone()))
+    })
+}
+
+#[test]
+fn test_freeze_survives_ledger_advance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let contract_id = env.register(LumenToken, ());
+    let client = LumenTokenClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &7,
+        &String::from_str(&env, "LumenPulse"),
+        &String::from_str(&env, "LMN"),
+    );
+    client.mint(&user1, &1000);
+    client.mint(&user2, &1000);
+
+    // Freezing extends the state entry like a balance write
+    client.freeze(&user1);
+    client.unfreeze(&user2);
+    assert_eq!(
+        state_ttl(&env, &contract_id, &user1),
+        balance::BALANCE_BUMP_AMOUNT
+    );
+
+    // Checking an account's state keeps it alive while the account is in use
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + balance::BALANCE_LIFETIME_THRESHOLD);
+    assert!(state_ttl(&env, &contract_id, &user2) < balance::BALANCE_LIFETIME_THRESHOLD);
+    client.transfer(&user2, &admin, &100);
+    assert_eq!(
+        state_ttl(&env, &contract_id, &user2),
+        balance::BALANCE_BUMP_AMOUNT
+    );
+
+    // A frozen account can't transact, but its freeze can still be bumped
+    assert!(state_ttl(&env, &contract_id, &user1) < balance::BALANCE_LIFETIME_THRESHOLD);
+    client.bump_balance_ttl(&user1);
+    assert_eq!(
+        state_ttl(&env, &contract_id, &user1),
+        balance::BALANCE_BUMP_AMOUNT
+    );
+    assert!(client.try_transfer(&user1, &user2, &100).is_err());
+}
@@ -44,7 +44,7 @@ fn test_lumenpulse_protocol_e2e() {
         &String::from_str(&env, "LUM"),
     );
     reg_client.initialize(&admin);
-    vault_client.initialize(&admin);
+    vault_client.initialize(&admin, &None);
 
     // 6. EXECUTION FLOW
     // Step A: Register the contributor in the registry
@@ -59,10 +59,12 @@ fn test_lumenpulse_protocol_e2e() {
         &Symbol::new(&env, "DevTools"),
         &5000i128,
         &token_id,
+        &None,
+        &Symbol::new(&env, "General"),
     );
 
     // Step D: Contributor deposits into the project
-    vault_client.deposit(&contributor, &project_id, &3000i128);
+    vault_client.deposit(&contributor, &project_id, &3000i128, &None);
 
     // 7. VERIFICATION (State Assertions)
     // Contributor should have 7,000 left (10,000 - 3,000)
@@ -71,8 +73,9 @@ fn test_lumenpulse_protocol_e2e() {
     assert_eq!(vault_client.get_balance(&project_id), 3000i128);
 
     // 8. WITHDRAWAL FLOW
-    // Admin must approve the milestone before withdrawal is possible
-    vault_client.approve_milestone(&admin, &project_id);
+    // Owner submits the milestone and admin must approve it before withdrawal is possible
+    vault_client.submit_milestone(&project_id, &0);
+    vault_client.approve_milestone(&admin, &project_id, &0);
 
     // Project owner withdraws 2,000 tokens
     vault_client.withdraw(&project_id, &2000i128);
@@ -189,3 +189,14 @@ fn test_old_admin_cannot_upgrade_after_rotation() {
     let dummy = BytesN::from_array(&env, &[0u8; 32]);
     client.upgrade(&admin, &dummy); // must panic – old admin rejected
 }
+
+// ---------------------------------------------------------------------------
+// 8. version() returns the compiled-in version identifier
+// ---------------------------------------------------------------------------
+#[test]
+fn test_version() {
+    let env = Env::default();
+    let (_, client) = setup(&env);
+
+    assert_eq!(client.version(), 1);
+}
@@ -146,6 +146,27 @@ impl VestingWalletContract {
 
     /// Claim available tokens based on linear vesting schedule
     pub fn claim(env: Env, beneficiary: Address) -> Result<i128, VestingError> {
+        Self::release(&env, beneficiary, i128::MAX)
+    }
+
+    /// Claim up to `amount` of the available tokens, leaving the rest claimable
+    ///
+    /// Transfers `min(amount, claimable)` and returns the amount transferred.
+    pub fn claim_partial(
+        env: Env,
+        beneficiary: Address,
+        amount: i128,
+    ) -> Result<i128, VestingError> {
+        // Validate amount
+        if amount <= 0 {
+            return Err(VestingError::InvalidAmount);
+        }
+
+        Self::release(&env, beneficiary, amount)
+    }
+
+    /// Transfer up to `max_amount` of the beneficiary's claimable tokens
+    fn release(env: &Env, beneficiary: Address, max_amount: i128) -> Result<i128, VestingError> {
         // Check if contract is initialized
         if !env.storage().instance().has(&DataKey::Admin) {
             return Err(VestingError::NotInitialized);
@@ -164,8 +185,9 @@ impl VestingWalletContract {
         // Get current time
         let current_time = env.ledger().timestamp();
 
-        // Calculate available amount using the helper function
-        let available_amount = Self::calculate_claimable_amount(current_time, &vesting);
+        // Calculate available amount using the helper function, capped at the request
+        let available_amount =
+            Self::calculate_claimable_amount(current_time, &vesting).min(max_amount);
 
         // Check if there's anything to claim
         if available_amount <= 0 {
@@ -182,7 +204,7 @@ impl VestingWalletContract {
         // Transfer tokens from contract to beneficiary
         let contract_address = env.current_contract_address();
         transfer(
-            &env,
+            env,
             &token,
             &contract_address,
             &beneficiary,
@@ -202,7 +224,7 @@ impl VestingWalletContract {
             amount_claimed: available_amount,
             remaining,
         }
-        .publish(&env);
+        .publish(env);
 
         Ok(available_amount)
     }
@@ -268,6 +290,11 @@ impl VestingWalletContract {
             .ok_or(VestingError::NotInitialized)
     }
 
+    /// Return this contract's version identifier.
+    pub fn version() -> u32 {
+        1
+    }
+
     /// Upgrade the contract WASM to a new hash.
     ///
     /// Only the stored admin may call this. Emits [`UpgradedEvent`] on success.
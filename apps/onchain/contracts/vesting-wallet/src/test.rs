@@ -333,6 +333,63 @@ fn test_claim_multiple_times() {
     assert_eq!(token_client.balance(&beneficiary), amount / 2);
 }
 
+#[test]
+fn test_claim_partial_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, beneficiary, token_client, _) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &token_client.address);
+
+    let current_time = env.ledger().timestamp();
+    let start_time = current_time + 100;
+    let duration = 10_000;
+    let amount: i128 = 1_000_000;
+
+    client.create_vesting(&admin, &beneficiary, &amount, &start_time, &duration);
+
+    // Fast forward to 50% through vesting period
+    env.ledger().set_timestamp(start_time + duration / 2);
+    let releasable = client.get_claimable(&beneficiary);
+    assert_eq!(releasable, amount / 2);
+
+    // Claim half of what is releasable
+    let claimed = client.claim_partial(&beneficiary, &(releasable / 2));
+    assert_eq!(claimed, releasable / 2);
+    assert_eq!(token_client.balance(&beneficiary), releasable / 2);
+    assert_eq!(
+        client.get_vesting(&beneficiary).claimed_amount,
+        releasable / 2
+    );
+
+    // The remainder is still claimable
+    assert_eq!(client.get_claimable(&beneficiary), releasable / 2);
+
+    // Requests above the releasable amount are capped
+    let claimed = client.claim_partial(&beneficiary, &amount);
+    assert_eq!(claimed, releasable / 2);
+    assert_eq!(token_client.balance(&beneficiary), releasable);
+}
+
+#[test]
+fn test_claim_partial_invalid_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, beneficiary, token_client, _) = setup_test(&env);
+
+    // Initialize contract
+    client.initialize(&admin, &token_client.address);
+
+    let result = client.try_claim_partial(&beneficiary, &0);
+    assert_eq!(result, Err(Ok(VestingError::InvalidAmount)));
+
+    let result = client.try_claim_partial(&beneficiary, &-5);
+    assert_eq!(result, Err(Ok(VestingError::InvalidAmount)));
+}
+
 #[test]
 fn test_claim_vesting_not_found() {
     let env = Env::default();
@@ -573,6 +630,15 @@ fn test_get_claimable_consistency_with_claim() {
     assert_eq!(claimable_after, 0);
 }
 
+#[test]
+fn test_version() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _, _, _, _) = setup_test(&env);
+    assert_eq!(client.version(), 1);
+}
+
 // ---------------------------------------------------------------------------
 // Upgradeability tests
 // ---------------------------------------------------------------------------